@@ -0,0 +1,185 @@
+use crate::schemas::ArchiveSettings;
+use crate::settings::{get_setting, set_setting};
+use crate::searches::Scheduler;
+use crate::server::record_status;
+use crate::helpers::get_db_path;
+
+use rusqlite::Connection;
+use tauri::{AppHandle, Manager};
+use log::{info, error};
+
+#[tauri::command]
+pub fn get_archive_settings(app_handle: AppHandle) -> Result<ArchiveSettings, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    get_setting(&conn, "archive")
+}
+
+#[tauri::command]
+pub fn set_archive_settings(app_handle: AppHandle, settings: ArchiveSettings) -> Result<(), String> {
+    if settings.after_days == 0 {
+        return Err("Jobs need to be at least a day old to be archived".to_string());
+    }
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    set_setting(&conn, "archive", &settings)
+}
+
+#[tauri::command]
+pub fn archive_stale_jobs(app_handle: AppHandle) -> Result<usize, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let settings: ArchiveSettings = get_setting(&conn, "archive")?;
+    archive_older_than(&conn, settings.after_days).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn purge_archived(app_handle: AppHandle, before: String) -> Result<usize, String> {
+    // VACUUM needs the database to itself, and every search, backup and restore holds this lock while it runs
+    let scheduler = app_handle.state::<Scheduler>();
+    let _guard = scheduler.lock().await;
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let before: String = conn.query_row("SELECT datetime(?1)", [before.trim()], |row| row.get::<_, Option<String>>(0))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Not a valid date: {}", before))?;
+
+    let purged = purge_before(&conn, &before).map_err(|e| e.to_string())?;
+
+    // The stats counters are left alone, they count every job ever seen rather than what is still stored
+    conn.execute("VACUUM", []).map_err(|e| e.to_string())?;
+
+    info!("Purged {} archived jobs from before {}", purged, before);
+    Ok(purged)
+}
+
+pub fn archive_stale(app_handle: &AppHandle) {
+    let result = Connection::open(get_db_path(app_handle))
+        .map_err(|e| e.to_string())
+        .and_then(|conn| {
+            let settings: ArchiveSettings = get_setting(&conn, "archive")?;
+            if !settings.enabled {
+                return Ok(0);
+            }
+            archive_older_than(&conn, settings.after_days).map_err(|e| e.to_string())
+        });
+
+    if let Err(e) = result {
+        error!("Failed to archive stale jobs: {}", e);
+    }
+}
+
+fn purge_before(conn: &Connection, before: &str) -> rusqlite::Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "CREATE TEMP TABLE purged AS SELECT id FROM jobs WHERE archived_at IS NOT NULL AND archived_at < ?1",
+        [before],
+    )?;
+    // The uniqueids are remembered so the next crawl doesn't bring the same jobs back
+    tx.execute(
+        "INSERT OR IGNORE INTO purged_uniqueids (uniqueid)
+         SELECT uniqueid FROM jobs WHERE id IN (SELECT id FROM purged) AND uniqueid IS NOT NULL",
+        [],
+    )?;
+    for table in ["job_history", "job_notes", "job_tags", "job_contacts", "reminders"] {
+        tx.execute(&format!("DELETE FROM {} WHERE job_id IN (SELECT id FROM purged)", table), [])?;
+    }
+    let purged = tx.execute("DELETE FROM jobs WHERE id IN (SELECT id FROM purged)", [])?;
+    tx.execute("DROP TABLE purged", [])?;
+    tx.commit()?;
+
+    Ok(purged)
+}
+
+fn archive_older_than(conn: &Connection, days: u32) -> rusqlite::Result<usize> {
+    // Only the untouched backlog goes, anything applied to, hidden by a rule or already read stays where it is
+    let job_ids = conn.prepare(
+        "SELECT id FROM jobs
+         WHERE read = 0 AND appliedto = 0 AND hidden = 0 AND archived_at IS NULL
+           AND datetime(fetched_date) < datetime('now', printf('-%d days', ?1))"
+    )?
+        .query_map([days], |row| row.get::<_, i32>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let tx = conn.unchecked_transaction()?;
+    for job_id in &job_ids {
+        tx.execute("UPDATE jobs SET archived_at = datetime('now') WHERE id = ?1", [job_id])?;
+        record_status(&tx, *job_id, "archived")?;
+    }
+    tx.commit()?;
+
+    if !job_ids.is_empty() {
+        info!("Archived {} jobs older than {} days", job_ids.len(), days);
+    }
+    Ok(job_ids.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+    use crate::ingest::{insert_job, process_new_jobs};
+    use crate::schemas::NewJob;
+
+    fn job(uniqueid: &str) -> NewJob {
+        NewJob {
+            uniqueid: uniqueid.to_string(),
+            title: "Backend Developer".to_string(),
+            source: "greenhouse".to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn unique_jobs(conn: &Connection) -> i32 {
+        conn.query_row("SELECT uniquejobs FROM stats WHERE id = 1", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn purged_jobs_are_not_crawled_again() {
+        let conn = test_connection();
+        let old = insert_job(&conn, &job("greenhouse:acme:1")).unwrap().unwrap();
+        insert_job(&conn, &job("greenhouse:acme:2")).unwrap().unwrap();
+        conn.execute("UPDATE jobs SET archived_at = '2024-01-01 00:00:00' WHERE id = ?1", [old]).unwrap();
+
+        assert_eq!(purge_before(&conn, "2024-06-01 00:00:00").unwrap(), 1);
+        assert_eq!(unique_jobs(&conn), 2);
+
+        assert_eq!(insert_job(&conn, &job("greenhouse:acme:1")).unwrap(), None);
+        assert_eq!(unique_jobs(&conn), 2);
+        let count: i32 = conn.query_row("SELECT COUNT(*) FROM jobs", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn purged_jobs_brought_back_by_the_engines_are_dropped() {
+        let conn = test_connection();
+        let old = insert_job(&conn, &job("indeed:abc")).unwrap().unwrap();
+        conn.execute("UPDATE jobs SET archived_at = '2024-01-01 00:00:00' WHERE id = ?1", [old]).unwrap();
+        purge_before(&conn, "2024-06-01 00:00:00").unwrap();
+
+        // The engines write rows and bump the counter themselves
+        for uniqueid in ["indeed:abc", "indeed:def"] {
+            conn.execute(
+                "INSERT INTO jobs (uniqueid, title, source, processed) VALUES (?1, 'Backend Developer', 'indeed', 0)",
+                [uniqueid],
+            ).unwrap();
+            conn.execute("UPDATE stats SET uniquejobs = uniquejobs + 1 WHERE id = 1", []).unwrap();
+        }
+
+        let processed = process_new_jobs(&conn, Some("indeed")).unwrap();
+        assert_eq!(processed.len(), 1);
+        let uniqueid: String = conn.query_row("SELECT uniqueid FROM jobs", [], |row| row.get(0)).unwrap();
+        assert_eq!(uniqueid, "indeed:def");
+        assert_eq!(unique_jobs(&conn), 2);
+    }
+
+    #[test]
+    fn only_jobs_archived_before_the_date_are_purged() {
+        let conn = test_connection();
+        let kept = insert_job(&conn, &job("greenhouse:acme:1")).unwrap().unwrap();
+        insert_job(&conn, &job("greenhouse:acme:2")).unwrap().unwrap();
+        conn.execute("UPDATE jobs SET archived_at = '2024-09-01 00:00:00' WHERE id = ?1", [kept]).unwrap();
+
+        assert_eq!(purge_before(&conn, "2024-06-01 00:00:00").unwrap(), 0);
+        let tombstones: i32 = conn.query_row("SELECT COUNT(*) FROM purged_uniqueids", [], |row| row.get(0)).unwrap();
+        assert_eq!(tombstones, 0);
+    }
+}
@@ -0,0 +1,413 @@
+use crate::schemas::{AtsBoards, CrawlPolicy, NewJob, SearchProgress, SearchReport};
+use crate::settings::{get_setting, set_setting};
+use crate::crawler::{fetch_with, load_policy, Crawler};
+use crate::html::html_to_text;
+use crate::ingest::{insert_job, matches_query, process_new_jobs};
+use crate::jobsearch::{before_deadline, emit_progress};
+use crate::helpers::get_db_path;
+
+use rusqlite::Connection;
+use serde_json::Value;
+use std::path::Path;
+use std::time::Instant;
+use log::{info, error};
+use tauri::{AppHandle, Manager};
+
+const GREENHOUSE_API: &str = "https://boards-api.greenhouse.io/v1/boards";
+const LEVER_API: &str = "https://api.lever.co/v0/postings";
+
+#[tauri::command]
+pub fn get_ats_boards(app_handle: AppHandle) -> Result<AtsBoards, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    get_setting(&conn, "ats_boards")
+}
+
+#[tauri::command]
+pub fn set_ats_boards(app_handle: AppHandle, boards: AtsBoards) -> Result<(), String> {
+    // Tokens are the company part of the board URL, e.g. "acme" in boards.greenhouse.io/acme
+    let clean = |tokens: &[String]| -> Result<Vec<String>, String> {
+        let mut cleaned: Vec<String> = Vec::new();
+        for token in tokens.iter().map(|token| token.trim().to_lowercase()).filter(|token| !token.is_empty()) {
+            if !token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(format!("{} doesn't look like a board token", token));
+            }
+            if !cleaned.contains(&token) {
+                cleaned.push(token);
+            }
+        }
+        Ok(cleaned)
+    };
+
+    let boards = AtsBoards {
+        greenhouse: clean(&boards.greenhouse)?,
+        lever: clean(&boards.lever)?,
+    };
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    set_setting(&conn, "ats_boards", &boards)
+}
+
+pub async fn run_ats(app_handle: &AppHandle, report: &mut SearchReport, deadline: Instant) -> Vec<i32> {
+    let boards = match get_ats_boards(app_handle.clone()) {
+        Ok(boards) => boards,
+        Err(e) => {
+            report.errors.push(e);
+            return Vec::new();
+        }
+    };
+    let tokens = if report.source == "lever" { boards.lever } else { boards.greenhouse };
+    if tokens.is_empty() {
+        report.errors.push(format!("No {} boards configured", report.source));
+        return Vec::new();
+    }
+    let policy = match load_policy(app_handle) {
+        Ok(policy) => policy,
+        Err(e) => {
+            report.errors.push(e);
+            return Vec::new();
+        }
+    };
+    let crawler = app_handle.state::<Crawler>();
+    let db_path = get_db_path(app_handle);
+    let api = if report.source == "lever" { LEVER_API } else { GREENHOUSE_API };
+
+    let mut new_job_ids = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        emit_progress(app_handle, &SearchProgress {
+            source: report.source.clone(),
+            page: i as i32 + 1,
+            total_pages: Some(tokens.len() as i32),
+            jobs_found: report.jobs_seen,
+            message: format!("Reading {} board {}", report.source, token),
+        });
+
+        match before_deadline(deadline, fetch_board(&crawler, &policy, &db_path, report, api, token)).await {
+            Ok(Ok(ids)) => new_job_ids.extend(ids),
+            Ok(Err(e)) => {
+                error!("Failed to read {} board {}: {}", report.source, token, e);
+                report.errors.push(format!("{}: {}", token, e));
+            }
+            Err(e) => {
+                // Out of time, the boards not read yet can wait for the next run
+                report.errors.push(format!("{}: {}", token, e));
+                break;
+            }
+        }
+        report.pages_fetched += 1;
+    }
+
+    new_job_ids
+}
+
+async fn fetch_board(
+    crawler: &Crawler,
+    policy: &CrawlPolicy,
+    db_path: &Path,
+    report: &mut SearchReport,
+    api: &str,
+    token: &str,
+) -> Result<Vec<i32>, String> {
+    let page = fetch_with(crawler, policy, &report.source, &board_url(api, &report.source, token)).await?;
+
+    // Opened after the fetch, a connection can't be held across an await in a spawned search
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    store_board(&conn, report, token, &page.body)
+}
+
+fn board_url(api: &str, source: &str, token: &str) -> String {
+    match source {
+        "lever" => format!("{}/{}?mode=json", api, token),
+        _ => format!("{}/{}/jobs?content=true", api, token),
+    }
+}
+
+fn store_board(conn: &Connection, report: &mut SearchReport, token: &str, body: &str) -> Result<Vec<i32>, String> {
+    let body: Value = serde_json::from_str(body).map_err(|e| format!("Unexpected response: {}", e))?;
+    let postings = match report.source.as_str() {
+        "lever" => parse_lever(&body, token),
+        _ => parse_greenhouse(&body, token),
+    };
+    let jobs: Vec<NewJob> = postings.into_iter()
+        .filter(|job| matches_query(job, &report.keywords, &report.location))
+        .collect();
+    report.jobs_seen += jobs.len() as i32;
+
+    for job in &jobs {
+        insert_job(conn, job).map_err(|e| e.to_string())?;
+    }
+
+    let new_job_ids: Vec<i32> = process_new_jobs(conn, Some(&report.source))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    info!("{} board {} had {} matching postings, {} new", report.source, token, jobs.len(), new_job_ids.len());
+
+    Ok(new_job_ids)
+}
+
+pub fn parse_greenhouse(body: &Value, token: &str) -> Vec<NewJob> {
+    body["jobs"].as_array()
+        .map(|jobs| jobs.iter().filter_map(|job| {
+            let id = job["id"].as_i64()?;
+            let url = text(&job["absolute_url"]);
+            Some(NewJob {
+                uniqueid: format!("greenhouse:{}:{}", token, id),
+                title: text(&job["title"]),
+                company: Some(text(&job["company_name"]))
+                    .filter(|name| !name.is_empty())
+                    .unwrap_or_else(|| company_from_token(token)),
+                location: text(&job["location"]["name"]),
+                salary: String::new(),
+                jobkey: url.clone(),
+                source: "greenhouse".to_string(),
+                // Content comes back entity-escaped, which html_to_text already copes with
+                description: html_to_text(&text(&job["content"])),
+                apply_url: url,
+                ..Default::default()
+            })
+        }).collect())
+        .unwrap_or_default()
+}
+
+pub fn parse_lever(body: &Value, token: &str) -> Vec<NewJob> {
+    body.as_array()
+        .map(|postings| postings.iter().filter_map(|posting| {
+            let id = posting["id"].as_str()?;
+            let url = text(&posting["hostedUrl"]);
+
+            let mut location = text(&posting["categories"]["location"]);
+            let workplace = text(&posting["workplaceType"]);
+            if !workplace.is_empty() && workplace != "unspecified" {
+                location = format!("{} ({})", location, workplace).trim().to_string();
+            }
+
+            // Lever splits the ad into an intro, titled bullet lists and a closing section
+            let mut html = text(&posting["description"]);
+            for list in posting["lists"].as_array().into_iter().flatten() {
+                html.push_str(&format!("<h3>{}</h3><ul>{}</ul>", text(&list["text"]), text(&list["content"])));
+            }
+            html.push_str(&text(&posting["additional"]));
+
+            Some(NewJob {
+                uniqueid: format!("lever:{}:{}", token, id),
+                title: text(&posting["text"]),
+                company: company_from_token(token),
+                location,
+                salary: lever_salary(&posting["salaryRange"]),
+                jobkey: url.clone(),
+                source: "lever".to_string(),
+                description: html_to_text(&html),
+                apply_url: Some(text(&posting["applyUrl"])).filter(|url| !url.is_empty()).unwrap_or(url),
+                ..Default::default()
+            })
+        }).collect())
+        .unwrap_or_default()
+}
+
+fn lever_salary(range: &Value) -> String {
+    let (min, max) = match (range["min"].as_f64(), range["max"].as_f64()) {
+        (Some(min), Some(max)) => (min, max),
+        _ => return String::new(),
+    };
+
+    // Written out so parse_salary can read it like any other listing
+    let interval = text(&range["interval"]);
+    let period = ["hour", "day", "week", "month", "year"].iter()
+        .find(|period| interval.contains(*period))
+        .unwrap_or(&"year");
+    format!("{} {} - {} per {}", text(&range["currency"]), min, max, period)
+}
+
+fn company_from_token(token: &str) -> String {
+    token.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn text(value: &Value) -> String {
+    value.as_str().unwrap_or_default().trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{test_connection, test_database_path};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const GREENHOUSE: &str = include_str!("../tests/fixtures/ats/greenhouse.json");
+    const LEVER: &str = include_str!("../tests/fixtures/ats/lever.json");
+
+    fn report(source: &str, keywords: &str, location: &str) -> SearchReport {
+        SearchReport {
+            source: source.to_string(),
+            keywords: keywords.to_string(),
+            location: location.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn policy() -> CrawlPolicy {
+        CrawlPolicy {
+            delay_ms: 0,
+            backoff_ms: 1,
+            max_retries: 2,
+            ..Default::default()
+        }
+    }
+
+    async fn serve(requests: usize, respond: fn(&str, usize) -> (u16, &'static str)) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        // Answers a fixed number of requests, one per connection, and hands back the request lines it was asked for
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let mut seen = Vec::new();
+            for i in 0..requests {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    let read = socket.read(&mut buf).await.unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                }
+                let line = String::from_utf8_lossy(&request).lines().next().unwrap_or_default().to_string();
+                let (status, body) = respond(&line, i);
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                seen.push(line);
+            }
+            seen
+        });
+        (address, server)
+    }
+
+    #[test]
+    fn parses_greenhouse_jobs() {
+        let jobs = parse_greenhouse(&serde_json::from_str(GREENHOUSE).unwrap(), "acme");
+        assert_eq!(jobs.len(), 2);
+
+        assert_eq!(jobs[0].uniqueid, "greenhouse:acme:4012345");
+        assert_eq!(jobs[0].title, "Senior Rust Engineer");
+        assert_eq!(jobs[0].company, "Acme Robotics");
+        assert_eq!(jobs[0].location, "London, United Kingdom");
+        assert_eq!(jobs[0].apply_url, "https://boards.greenhouse.io/acme/jobs/4012345");
+        assert_eq!(jobs[0].description, "We build robot fleets.\nWhat you'll need\n- Rust in production\n- Tokio");
+
+        // An empty company name falls back to the board token
+        assert_eq!(jobs[1].company, "Acme");
+    }
+
+    #[test]
+    fn parses_lever_postings() {
+        let jobs = parse_lever(&serde_json::from_str(LEVER).unwrap(), "ferris-works");
+        assert_eq!(jobs.len(), 2);
+
+        assert_eq!(jobs[0].uniqueid, "lever:ferris-works:5d1c2b3a-8f6e-4c1d-9a0b-123456789abc");
+        assert_eq!(jobs[0].title, "Platform Engineer");
+        assert_eq!(jobs[0].company, "Ferris Works");
+        assert_eq!(jobs[0].location, "Berlin (hybrid)");
+        assert_eq!(jobs[0].salary, "EUR 70000 - 90000 per year");
+        assert_eq!(jobs[0].description, "Join the platform team.\nRequirements\n- Go or Rust\n- Kubernetes\nWe offer a learning budget.");
+        assert!(jobs[0].apply_url.ends_with("/apply"));
+
+        assert_eq!(jobs[1].salary, "");
+        assert_eq!(jobs[1].location, "Remote (remote)");
+    }
+
+    #[test]
+    fn unexpected_bodies_are_errors() {
+        let conn = test_connection();
+        assert!(store_board(&conn, &mut report("greenhouse", "", ""), "acme", "<html>Not found</html>").is_err());
+    }
+
+    #[test]
+    fn only_postings_matching_the_query_are_stored() {
+        let conn = test_connection();
+        let mut rust_report = report("greenhouse", "rust", "london");
+        let stored = store_board(&conn, &mut rust_report, "acme", GREENHOUSE).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(rust_report.jobs_seen, 1);
+
+        let stored = store_board(&conn, &mut report("greenhouse", "", "london"), "acme", GREENHOUSE).unwrap();
+        let title: String = conn.query_row("SELECT title FROM jobs WHERE id = ?1", [stored[0]], |row| row.get(0)).unwrap();
+        assert_eq!(title, "Product Marketing Manager");
+    }
+
+    #[tokio::test]
+    async fn fetches_and_stores_a_greenhouse_board() {
+        let (address, server) = serve(2, |line, _| match line {
+            line if line.starts_with("GET /robots.txt") => (404, ""),
+            _ => (200, GREENHOUSE),
+        }).await;
+        let db_path = test_database_path("greenhouse-board");
+        let api = format!("{}/v1/boards", address);
+        let crawler = Crawler::default();
+
+        let mut board_report = report("greenhouse", "", "");
+        let stored = fetch_board(&crawler, &policy(), &db_path, &mut board_report, &api, "acme").await.unwrap();
+        assert_eq!(stored.len(), 2);
+        assert_eq!(server.await.unwrap(), vec![
+            "GET /robots.txt HTTP/1.1".to_string(),
+            "GET /v1/boards/acme/jobs?content=true HTTP/1.1".to_string(),
+        ]);
+
+        // A second fetch of the same board adds nothing new
+        let (address, server) = serve(2, |line, _| match line {
+            line if line.starts_with("GET /robots.txt") => (404, ""),
+            _ => (200, GREENHOUSE),
+        }).await;
+        let api = format!("{}/v1/boards", address);
+        let stored = fetch_board(&crawler, &policy(), &db_path, &mut board_report, &api, "acme").await;
+        server.await.unwrap();
+        assert!(stored.unwrap().is_empty());
+        assert_eq!(board_report.jobs_seen, 4);
+    }
+
+    #[tokio::test]
+    async fn busy_boards_are_retried() {
+        let (address, server) = serve(3, |line, i| match line {
+            line if line.starts_with("GET /robots.txt") => (404, ""),
+            _ if i == 1 => (503, ""),
+            _ => (200, LEVER),
+        }).await;
+        let db_path = test_database_path("lever-retry");
+        let api = format!("{}/v0/postings", address);
+
+        let mut board_report = report("lever", "platform", "");
+        let stored = fetch_board(&Crawler::default(), &policy(), &db_path, &mut board_report, &api, "ferris-works").await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(server.await.unwrap()[1..], [
+            "GET /v0/postings/ferris-works?mode=json HTTP/1.1".to_string(),
+            "GET /v0/postings/ferris-works?mode=json HTTP/1.1".to_string(),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn robots_txt_can_refuse_a_board() {
+        let (address, server) = serve(1, |_, _| (200, "User-agent: *\nDisallow: /v1/")).await;
+        let db_path = test_database_path("greenhouse-robots");
+        let api = format!("{}/v1/boards", address);
+
+        let result = fetch_board(&Crawler::default(), &policy(), &db_path, &mut report("greenhouse", "", ""), &api, "acme").await;
+        assert!(result.unwrap_err().contains("robots.txt"));
+        // Only robots.txt was asked for
+        assert_eq!(server.await.unwrap().len(), 1);
+    }
+}
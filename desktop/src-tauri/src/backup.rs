@@ -0,0 +1,362 @@
+use crate::schemas::{BackupManifest, BackupFile};
+use crate::database::migrate;
+use crate::searches::Scheduler;
+use crate::helpers::get_db_path;
+
+use rusqlite::Connection;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::ffi::OsStr;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use log::info;
+use tauri::{AppHandle, Manager};
+
+const BACKUP_FORMAT: &str = "solicit-backup";
+const BACKUP_VERSION: u32 = 1;
+const DATABASE_ENTRY: &str = "insegnante.sqlite";
+const SECRET_FILES: &[&str] = &["credentials.json"];
+const DATA_FILES: &[&str] = &[
+    "config.json", "jobDescription.json", "applicant_details.json", "cv_details.json", "credentials.json",
+];
+
+#[tauri::command]
+pub async fn create_backup(app_handle: AppHandle, path: String, include_secrets: bool) -> Result<BackupManifest, String> {
+    let app_dir = app_data_dir(&app_handle)?;
+    // Every search takes this lock too, so nothing is writing to the database while it is copied
+    let scheduler = app_handle.state::<Scheduler>();
+    let _guard = scheduler.lock().await;
+
+    let manifest = backup_to(&app_dir, &get_db_path(&app_handle), Path::new(&path), include_secrets)?;
+
+    info!("Backed up {} files to {}", manifest.files.len(), path);
+    Ok(manifest)
+}
+
+#[tauri::command]
+pub async fn restore_backup(app_handle: AppHandle, path: String) -> Result<BackupManifest, String> {
+    let app_dir = app_data_dir(&app_handle)?;
+    // Held until the restore is done, so no search writes to the database while it is swapped out
+    let scheduler = app_handle.state::<Scheduler>();
+    let _guard = scheduler.lock().await;
+
+    let manifest = restore_from(&app_dir, &get_db_path(&app_handle), Path::new(&path))?;
+
+    info!("Restored backup from {} made on {}", path, manifest.created_at);
+    let _ = app_handle.emit_all("backup-restored", &manifest);
+    Ok(manifest)
+}
+
+fn backup_to(app_dir: &Path, db_path: &Path, path: &Path, include_secrets: bool) -> Result<BackupManifest, String> {
+    // VACUUM INTO gives a consistent copy even if the database was mid-write
+    let snapshot = app_dir.join("backup-snapshot.sqlite");
+    let _ = fs::remove_file(&snapshot);
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    conn.execute("VACUUM INTO ?1", [snapshot.to_string_lossy()]).map_err(|e| e.to_string())?;
+    let created_at: String = conn.query_row("SELECT datetime('now')", [], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+
+    let mut entries: Vec<(String, PathBuf)> = vec![(DATABASE_ENTRY.to_string(), snapshot.clone())];
+    for name in DATA_FILES {
+        if !include_secrets && SECRET_FILES.contains(name) {
+            continue;
+        }
+        let file_path = app_dir.join(name);
+        if file_path.exists() {
+            entries.push((name.to_string(), file_path));
+        }
+    }
+    if let Some(cv_path) = cv_path(app_dir) {
+        let name = cv_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        entries.push((format!("cv/{}", name), cv_path));
+    }
+
+    let result = write_archive(path, &entries, created_at, include_secrets);
+    let _ = fs::remove_file(&snapshot);
+    result
+}
+
+fn restore_from(app_dir: &Path, db_path: &Path, path: &Path) -> Result<BackupManifest, String> {
+    let mut archive = ZipArchive::new(File::open(path).map_err(|e| e.to_string())?)
+        .map_err(|e| format!("Not a backup archive: {}", e))?;
+
+    let manifest: BackupManifest = serde_json::from_slice(&read_entry(&mut archive, "manifest.json")?)
+        .map_err(|e| format!("Invalid backup manifest: {}", e))?;
+    if manifest.format != BACKUP_FORMAT {
+        return Err("This file isn't a Solicit backup".to_string());
+    }
+    if manifest.version > BACKUP_VERSION {
+        return Err(format!("This backup was made by a newer version of Solicit ({})", manifest.app_version));
+    }
+
+    // Everything is read and checked before anything on disk is touched
+    let mut contents = Vec::new();
+    for file in &manifest.files {
+        if file.name.contains("..") || file.name.starts_with('/') {
+            return Err(format!("Unsafe path in backup: {}", file.name));
+        }
+        let data = read_entry(&mut archive, &file.name)?;
+        if data.len() as u64 != file.size || sha256_hex(&data) != file.sha256 {
+            return Err(format!("{} is corrupted in this backup", file.name));
+        }
+        contents.push((file.name.clone(), data));
+    }
+    if !contents.iter().any(|(name, _)| name == DATABASE_ENTRY) {
+        return Err("This backup has no database".to_string());
+    }
+
+    let staged = app_dir.join("restore-staged.sqlite");
+    for (name, data) in &contents {
+        if name == DATABASE_ENTRY {
+            fs::write(&staged, data).map_err(|e| e.to_string())?;
+            if let Err(e) = check_database(&staged) {
+                let _ = fs::remove_file(&staged);
+                return Err(e);
+            }
+        }
+    }
+
+    // Keep the current database around in case the restore was a mistake
+    if db_path.exists() {
+        fs::copy(db_path, app_dir.join("insegnante.before-restore.sqlite")).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&staged, db_path).or_else(|_| {
+        fs::copy(&staged, db_path).map(|_| ()).and_then(|_| fs::remove_file(&staged))
+    }).map_err(|e| e.to_string())?;
+
+    for (name, data) in &contents {
+        let target = match name.strip_prefix("cv/") {
+            Some(cv_name) => match cv_file_name(cv_name) {
+                Some(file_name) => app_dir.join(file_name),
+                None => continue,
+            },
+            None if name == DATABASE_ENTRY => continue,
+            None if DATA_FILES.contains(&name.as_str()) => app_dir.join(name),
+            None => continue,
+        };
+        fs::write(&target, data).map_err(|e| e.to_string())?;
+    }
+
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    migrate(&conn).map_err(|e| e.to_string())?;
+
+    Ok(manifest)
+}
+
+fn write_archive(path: &Path, entries: &[(String, PathBuf)], created_at: String, includes_secrets: bool) -> Result<BackupManifest, String> {
+    let mut zip = ZipWriter::new(File::create(path).map_err(|e| e.to_string())?);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut manifest = BackupManifest {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at,
+        includes_secrets,
+        files: Vec::new(),
+    };
+
+    for (name, file_path) in entries {
+        let data = fs::read(file_path).map_err(|e| format!("Failed to read {}: {}", name, e))?;
+        zip.start_file(name.as_str(), options).map_err(|e| e.to_string())?;
+        zip.write_all(&data).map_err(|e| e.to_string())?;
+        manifest.files.push(BackupFile {
+            name: name.clone(),
+            size: data.len() as u64,
+            sha256: sha256_hex(&data),
+        });
+    }
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    zip.start_file("manifest.json", options).map_err(|e| e.to_string())?;
+    zip.write_all(&manifest_json).map_err(|e| e.to_string())?;
+    zip.finish().map_err(|e| e.to_string())?;
+
+    Ok(manifest)
+}
+
+fn cv_file_name(name: &str) -> Option<&OsStr> {
+    // CVs are written straight into the app folder, so anything with a directory, drive or share in it is skipped
+    Path::new(name).file_name().filter(|file_name| *file_name == OsStr::new(name))
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<Vec<u8>, String> {
+    let mut entry = archive.by_name(name).map_err(|_| format!("{} is missing from the backup", name))?;
+    let mut data = Vec::new();
+    entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+    Ok(data)
+}
+
+fn check_database(path: &Path) -> Result<(), String> {
+    let conn = Connection::open(path).map_err(|e| e.to_string())?;
+    let integrity: String = conn.query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("The backed up database can't be read: {}", e))?;
+    if integrity != "ok" {
+        return Err(format!("The backed up database is damaged: {}", integrity));
+    }
+
+    let has_jobs: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'jobs'",
+        [],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+    if !has_jobs {
+        return Err("The backed up database has no jobs table".to_string());
+    }
+    Ok(())
+}
+
+fn cv_path(app_dir: &Path) -> Option<PathBuf> {
+    // The CV itself lives next to config.json under the name stored there
+    let config: Value = serde_json::from_str(&fs::read_to_string(app_dir.join("config.json")).ok()?).ok()?;
+    let file_name = Path::new(config["cvFilename"].as_str()?).file_name()?.to_owned();
+    let path = app_dir.join(file_name);
+    if path.is_file() { Some(path) } else { None }
+}
+
+fn app_data_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle.path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "Failed to get app data dir".to_string())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_database_path;
+
+    fn app_dir(name: &str) -> (PathBuf, PathBuf) {
+        // A throwaway app folder with a migrated database, a config pointing at a CV, the CV and credentials
+        let dir = std::env::temp_dir().join(format!("solicit-backup-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let db_path = dir.join("insegnante.sqlite");
+        fs::rename(test_database_path(name), &db_path).unwrap();
+        Connection::open(&db_path).unwrap()
+            .execute("INSERT INTO jobs (uniqueid, title) VALUES ('kept', 'Backend Developer')", [])
+            .unwrap();
+
+        fs::write(dir.join("config.json"), r#"{"cvFilename": "resume.pdf"}"#).unwrap();
+        fs::write(dir.join("resume.pdf"), b"%PDF-1.4 original").unwrap();
+        fs::write(dir.join("credentials.json"), r#"{"anthropic_api_key": "secret"}"#).unwrap();
+        (dir, db_path)
+    }
+
+    fn job_titles(db_path: &Path) -> Vec<String> {
+        let conn = Connection::open(db_path).unwrap();
+        let mut stmt = conn.prepare("SELECT title FROM jobs ORDER BY id").unwrap();
+        let titles = stmt.query_map([], |row| row.get(0)).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        titles
+    }
+
+    fn rewrite_archive(from: &Path, to: &Path, change: impl Fn(&str, Vec<u8>) -> Vec<u8>) {
+        // Copies every entry, letting the test tamper with some of them on the way
+        let mut archive = ZipArchive::new(File::open(from).unwrap()).unwrap();
+        let mut zip = ZipWriter::new(File::create(to).unwrap());
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).unwrap();
+            let name = entry.name().to_string();
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data).unwrap();
+            zip.start_file(name.as_str(), FileOptions::default()).unwrap();
+            zip.write_all(&change(&name, data)).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn backups_restore_what_was_saved() {
+        let (dir, db_path) = app_dir("round-trip");
+        let archive = dir.join("backup.zip");
+
+        let manifest = backup_to(&dir, &db_path, &archive, false).unwrap();
+        let names: Vec<&str> = manifest.files.iter().map(|file| file.name.as_str()).collect();
+        assert_eq!(names, vec![DATABASE_ENTRY, "config.json", "cv/resume.pdf"]);
+        assert!(!manifest.includes_secrets);
+
+        // Change everything after the backup was taken
+        Connection::open(&db_path).unwrap()
+            .execute("INSERT INTO jobs (uniqueid, title) VALUES ('later', 'Added after the backup')", [])
+            .unwrap();
+        fs::write(dir.join("config.json"), r#"{"cvFilename": "other.pdf"}"#).unwrap();
+        fs::write(dir.join("resume.pdf"), b"changed").unwrap();
+
+        restore_from(&dir, &db_path, &archive).unwrap();
+        assert_eq!(job_titles(&db_path), vec!["Backend Developer".to_string()]);
+        assert_eq!(fs::read_to_string(dir.join("config.json")).unwrap(), r#"{"cvFilename": "resume.pdf"}"#);
+        assert_eq!(fs::read(dir.join("resume.pdf")).unwrap(), b"%PDF-1.4 original");
+        // Secrets weren't in the backup, so the current ones are left alone
+        assert!(fs::read_to_string(dir.join("credentials.json")).unwrap().contains("secret"));
+        // The replaced database is kept in case the restore was a mistake
+        assert_eq!(job_titles(&dir.join("insegnante.before-restore.sqlite")).len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn damaged_backups_are_rejected_before_anything_changes() {
+        let (dir, db_path) = app_dir("damaged");
+        let archive = dir.join("backup.zip");
+        backup_to(&dir, &db_path, &archive, true).unwrap();
+
+        // One archive with a changed file, one with a manifest checksum that no longer matches
+        let corrupted = dir.join("corrupted.zip");
+        rewrite_archive(&archive, &corrupted, |name, mut data| {
+            if name == "config.json" {
+                data[0] = b'[';
+            }
+            data
+        });
+        let bad_checksum = dir.join("bad-checksum.zip");
+        rewrite_archive(&archive, &bad_checksum, |name, data| {
+            if name != "manifest.json" {
+                return data;
+            }
+            let mut manifest: BackupManifest = serde_json::from_slice(&data).unwrap();
+            manifest.files[0].sha256 = "0".repeat(64);
+            serde_json::to_vec(&manifest).unwrap()
+        });
+
+        Connection::open(&db_path).unwrap()
+            .execute("INSERT INTO jobs (uniqueid, title) VALUES ('later', 'Added after the backup')", [])
+            .unwrap();
+        let database_before = fs::read(&db_path).unwrap();
+
+        let error = restore_from(&dir, &db_path, &corrupted).unwrap_err();
+        assert_eq!(error, "config.json is corrupted in this backup");
+        let error = restore_from(&dir, &db_path, &bad_checksum).unwrap_err();
+        assert_eq!(error, format!("{} is corrupted in this backup", DATABASE_ENTRY));
+
+        assert_eq!(fs::read(&db_path).unwrap(), database_before);
+        assert_eq!(fs::read_to_string(dir.join("config.json")).unwrap(), r#"{"cvFilename": "resume.pdf"}"#);
+        assert!(!dir.join("insegnante.before-restore.sqlite").exists());
+        assert!(!dir.join("restore-staged.sqlite").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn cv_entries_stay_in_the_app_folder() {
+        assert_eq!(cv_file_name("resume.pdf"), Some(OsStr::new("resume.pdf")));
+        assert_eq!(cv_file_name("nested/resume.pdf"), None);
+        assert_eq!(cv_file_name("/etc/passwd"), None);
+        assert_eq!(cv_file_name(".."), None);
+        assert_eq!(cv_file_name(""), None);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_drive_and_share_paths_are_skipped() {
+        assert_eq!(cv_file_name("C:\\Users\\me\\evil.exe"), None);
+        assert_eq!(cv_file_name("C:evil.exe"), None);
+        assert_eq!(cv_file_name("\\\\server\\share\\evil.exe"), None);
+    }
+}
@@ -0,0 +1,183 @@
+#[derive(Debug, Default, Clone)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: String,
+    pub location: String,
+    pub url: String,
+    pub categories: String,
+    pub starts_at: String,
+    pub ends_at: Option<String>,
+    pub all_day: bool,
+    pub alarm_minutes: Option<i32>,
+    pub sequence: i32,
+    pub last_modified: Option<String>,
+    pub cancelled: bool,
+}
+
+pub fn write_calendar(events: &[CalendarEvent], stamp: &str) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Solicit//Job Hunter//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Solicit".to_string(),
+    ];
+
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        // Calendar apps replace an event with the same UID when SEQUENCE goes up, instead of adding a copy
+        lines.push(format!("SEQUENCE:{}", event.sequence));
+        if let Some(last_modified) = &event.last_modified {
+            lines.push(format!("LAST-MODIFIED:{}Z", ics_datetime(last_modified)));
+        }
+        if event.all_day {
+            lines.push(format!("DTSTART;VALUE=DATE:{}", ics_date(&event.starts_at)));
+            if let Some(ends_at) = &event.ends_at {
+                lines.push(format!("DTEND;VALUE=DATE:{}", ics_date(ends_at)));
+            }
+        } else {
+            // Times are stored as local wall clock, so they go out as floating times rather than UTC
+            lines.push(format!("DTSTART:{}", ics_datetime(&event.starts_at)));
+            if let Some(ends_at) = &event.ends_at {
+                lines.push(format!("DTEND:{}", ics_datetime(ends_at)));
+            }
+        }
+        // Finished items stay in the feed as cancelled so calendars drop them rather than keep a stale copy
+        lines.push(format!("STATUS:{}", if event.cancelled { "CANCELLED" } else { "CONFIRMED" }));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        if !event.categories.is_empty() {
+            lines.push(format!("CATEGORIES:{}", escape_text(&event.categories)));
+        }
+        if !event.description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&event.description)));
+        }
+        if !event.location.is_empty() {
+            lines.push(format!("LOCATION:{}", escape_text(&event.location)));
+        }
+        if !event.url.is_empty() {
+            lines.push(format!("URL:{}", event.url));
+        }
+        if let Some(minutes) = event.alarm_minutes.filter(|_| !event.cancelled) {
+            lines.push("BEGIN:VALARM".to_string());
+            lines.push("ACTION:DISPLAY".to_string());
+            lines.push(format!("DESCRIPTION:{}", escape_text(&event.summary)));
+            lines.push(format!("TRIGGER:-PT{}M", minutes));
+            lines.push("END:VALARM".to_string());
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line)).collect::<Vec<_>>().join("")
+}
+
+fn ics_datetime(datetime: &str) -> String {
+    // "2024-05-01 14:30" or "2024-05-01 14:30:00" becomes 20240501T143000
+    let digits: String = datetime.chars().filter(|c| c.is_ascii_digit()).collect();
+    format!("{:0<8}T{:0<6}", &digits[..digits.len().min(8)], digits.get(8..).unwrap_or(""))
+}
+
+fn ics_date(datetime: &str) -> String {
+    ics_datetime(datetime)[..8].to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn fold_line(line: &str) -> String {
+    // RFC 5545 caps lines at 75 octets, continuations start with a space
+    let mut folded = String::with_capacity(line.len() + 8);
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event() -> CalendarEvent {
+        CalendarEvent {
+            uid: "reminder-7@solicit".to_string(),
+            summary: "Interview: Rust Developer at Ferris, Inc.".to_string(),
+            description: "Bring the portfolio\nAsk about remote days".to_string(),
+            starts_at: "2024-05-01 14:30".to_string(),
+            ends_at: Some("2024-05-01 15:30".to_string()),
+            alarm_minutes: Some(30),
+            sequence: 2,
+            last_modified: Some("2024-04-20 09:15:00".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn lines(ics: &str) -> Vec<&str> {
+        ics.split("\r\n").collect()
+    }
+
+    #[test]
+    fn writes_a_timed_event() {
+        let ics = write_calendar(&[event()], "20240420T091500Z");
+        let lines = lines(&ics);
+
+        assert!(lines.contains(&"UID:reminder-7@solicit"));
+        assert!(lines.contains(&"SEQUENCE:2"));
+        assert!(lines.contains(&"LAST-MODIFIED:20240420T091500Z"));
+        assert!(lines.contains(&"DTSTART:20240501T143000"));
+        assert!(lines.contains(&"DTEND:20240501T153000"));
+        assert!(lines.contains(&"STATUS:CONFIRMED"));
+        assert!(lines.contains(&"SUMMARY:Interview: Rust Developer at Ferris\\, Inc."));
+        assert!(lines.contains(&"DESCRIPTION:Bring the portfolio\\nAsk about remote days"));
+        assert!(lines.contains(&"TRIGGER:-PT30M"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn cancelled_events_keep_their_uid_and_lose_the_alarm() {
+        let cancelled = CalendarEvent { cancelled: true, sequence: 3, ..event() };
+        let ics = write_calendar(&[cancelled], "20240420T091500Z");
+        let lines = lines(&ics);
+
+        assert!(lines.contains(&"UID:reminder-7@solicit"));
+        assert!(lines.contains(&"SEQUENCE:3"));
+        assert!(lines.contains(&"STATUS:CANCELLED"));
+        assert!(!ics.contains("BEGIN:VALARM"));
+    }
+
+    #[test]
+    fn all_day_events_use_dates() {
+        let deadline = CalendarEvent {
+            all_day: true,
+            starts_at: "2024-05-10 00:00".to_string(),
+            ends_at: Some("2024-05-11".to_string()),
+            ..event()
+        };
+        let ics = write_calendar(&[deadline], "20240420T091500Z");
+        assert!(lines(&ics).contains(&"DTSTART;VALUE=DATE:20240510"));
+        assert!(lines(&ics).contains(&"DTEND;VALUE=DATE:20240511"));
+    }
+
+    #[test]
+    fn long_lines_are_folded_at_75_octets() {
+        let long = CalendarEvent { summary: "é".repeat(60), ..event() };
+        let ics = write_calendar(&[long], "20240420T091500Z");
+        assert!(lines(&ics).iter().all(|line| line.len() <= 75));
+        assert!(ics.contains("\r\n é"));
+    }
+}
@@ -0,0 +1,304 @@
+use crate::schemas::{BlacklistSettings, Company};
+use crate::settings::{get_setting, set_setting};
+use crate::server::record_status;
+use crate::helpers::get_db_path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::AppHandle;
+use log::{info, error};
+
+const LEGAL_SUFFIXES: &[&str] = &[
+    "ltd", "limited", "inc", "incorporated", "llc", "llp", "plc", "corp", "corporation", "co", "gmbh", "ag",
+    "srl", "spa", "sas", "sa", "bv", "nv", "pty",
+];
+
+const COMPANY_COLUMNS: &str = "companies.id, companies.name, companies.website, companies.notes, companies.rating,
+    companies.blocked, companies.recruiter, (SELECT COUNT(*) FROM jobs WHERE jobs.company_id = companies.id)";
+
+#[tauri::command]
+pub fn list_companies(app_handle: AppHandle) -> Result<Vec<Company>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM companies ORDER BY companies.name COLLATE NOCASE", COMPANY_COLUMNS))
+        .map_err(|e| e.to_string())?;
+
+    let companies = stmt.query_map([], company_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(companies)
+}
+
+#[tauri::command]
+pub fn add_company(app_handle: AppHandle, company: Company) -> Result<Company, String> {
+    // Lets a recruiter be blocked before any of their listings turn up
+    validate_company(&company)?;
+    let normalised = normalise_company(&company.name);
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    if find_company(&conn, &normalised).map_err(|e| e.to_string())?.is_some() {
+        return Err(format!("{} is already in your companies", company.name.trim()));
+    }
+
+    conn.execute(
+        "INSERT INTO companies (name, normalised_name, website, notes, rating, blocked, recruiter)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            company.name.trim(), normalised, company.website.trim(), company.notes.trim(), company.rating,
+            company.blocked, company.recruiter
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_company(&conn, conn.last_insert_rowid() as i32)
+}
+
+#[tauri::command]
+pub fn update_company(app_handle: AppHandle, company_id: i32, company: Company) -> Result<Company, String> {
+    validate_company(&company)?;
+    let normalised = normalise_company(&company.name);
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    save_company(&conn, company_id, &company, &normalised)?;
+    apply_blacklist(&conn).map_err(|e| e.to_string())?;
+
+    get_company(&conn, company_id)
+}
+
+#[tauri::command]
+pub fn set_company_blocked(app_handle: AppHandle, company_id: i32, blocked: bool) -> Result<Company, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("UPDATE companies SET blocked = ?1 WHERE id = ?2", params![blocked, company_id])
+        .map_err(|e| e.to_string())?;
+    apply_blacklist(&conn).map_err(|e| e.to_string())?;
+
+    get_company(&conn, company_id)
+}
+
+#[tauri::command]
+pub fn get_blacklist_settings(app_handle: AppHandle) -> Result<BlacklistSettings, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    get_setting(&conn, "blacklist")
+}
+
+#[tauri::command]
+pub fn set_blacklist_settings(app_handle: AppHandle, settings: BlacklistSettings) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    set_setting(&conn, "blacklist", &settings)?;
+    apply_blacklist(&conn).map_err(|e| e.to_string())
+}
+
+pub fn link_company(conn: &Connection, name: &str) -> rusqlite::Result<Option<i32>> {
+    // Companies are created the first time a job mentions them
+    let normalised = normalise_company(name);
+    if normalised.is_empty() {
+        return Ok(None);
+    }
+
+    if let Some(company_id) = find_company(conn, &normalised)? {
+        return Ok(Some(company_id));
+    }
+    conn.execute(
+        "INSERT INTO companies (name, normalised_name) VALUES (?1, ?2)",
+        params![name.trim(), normalised],
+    )?;
+
+    Ok(Some(conn.last_insert_rowid() as i32))
+}
+
+pub fn blacklist_clause(conn: &Connection) -> String {
+    format!(
+        " AND (company_id IS NULL OR company_id NOT IN (SELECT id FROM companies WHERE {}))",
+        blocked_condition(conn)
+    )
+}
+
+fn blacklist_settings(conn: &Connection) -> BlacklistSettings {
+    get_setting(conn, "blacklist").unwrap_or_else(|e| {
+        error!("Failed to load blacklist settings: {}", e);
+        BlacklistSettings::default()
+    })
+}
+
+fn blocked_condition(conn: &Connection) -> &'static str {
+    if blacklist_settings(conn).block_recruiters {
+        "blocked = 1 OR recruiter = 1"
+    } else {
+        "blocked = 1"
+    }
+}
+
+pub fn apply_blacklist(conn: &Connection) -> rusqlite::Result<()> {
+    // Hiding happens at query time, only marking read needs touching the jobs themselves
+    if !blacklist_settings(conn).mark_read {
+        return Ok(());
+    }
+
+    let job_ids = conn.prepare(&format!(
+        "SELECT id FROM jobs WHERE read = 0 AND company_id IN (SELECT id FROM companies WHERE {})",
+        blocked_condition(conn)
+    ))?
+        .query_map([], |row| row.get::<_, i32>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for job_id in &job_ids {
+        conn.execute("UPDATE jobs SET read = 1 WHERE id = ?1", [job_id])?;
+        record_status(conn, *job_id, "read")?;
+    }
+    if !job_ids.is_empty() {
+        info!("Marked {} jobs from blocked companies as read", job_ids.len());
+    }
+
+    Ok(())
+}
+
+fn save_company(conn: &Connection, company_id: i32, company: &Company, normalised: &str) -> Result<(), String> {
+    if let Some(existing) = find_company(conn, normalised).map_err(|e| e.to_string())? {
+        if existing != company_id {
+            return Err(format!("{} is already in your companies", company.name.trim()));
+        }
+    }
+
+    // Jobs keep being posted under the old name, so it stays an alias of the renamed company
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute(
+        "INSERT OR IGNORE INTO company_aliases (normalised_name, company_id)
+         SELECT normalised_name, id FROM companies WHERE id = ?1 AND normalised_name != ?2",
+        params![company_id, normalised],
+    ).map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM company_aliases WHERE normalised_name = ?1", [normalised])
+        .map_err(|e| e.to_string())?;
+    tx.execute(
+        "UPDATE companies SET name = ?1, normalised_name = ?2, website = ?3, notes = ?4, rating = ?5, blocked = ?6,
+            recruiter = ?7
+         WHERE id = ?8",
+        params![
+            company.name.trim(), normalised, company.website.trim(), company.notes.trim(), company.rating,
+            company.blocked, company.recruiter, company_id
+        ],
+    ).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+fn find_company(conn: &Connection, normalised: &str) -> rusqlite::Result<Option<i32>> {
+    conn.query_row(
+        "SELECT id FROM companies WHERE normalised_name = ?1
+         UNION ALL
+         SELECT company_id FROM company_aliases WHERE normalised_name = ?1
+         LIMIT 1",
+        [normalised],
+        |row| row.get(0),
+    ).optional()
+}
+
+fn get_company(conn: &Connection, company_id: i32) -> Result<Company, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM companies WHERE companies.id = ?1", COMPANY_COLUMNS),
+        [company_id],
+        company_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+fn company_from_row(row: &rusqlite::Row) -> rusqlite::Result<Company> {
+    Ok(Company {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        website: row.get(2)?,
+        notes: row.get(3)?,
+        rating: row.get(4)?,
+        blocked: row.get(5)?,
+        recruiter: row.get(6)?,
+        job_count: row.get(7)?,
+    })
+}
+
+fn validate_company(company: &Company) -> Result<(), String> {
+    if normalise_company(&company.name).is_empty() {
+        return Err("The company needs a name".to_string());
+    }
+    if let Some(rating) = company.rating {
+        if !(1..=5).contains(&rating) {
+            return Err("Ratings go from 1 to 5".to_string());
+        }
+    }
+    Ok(())
+}
+
+pub fn normalise_company(name: &str) -> String {
+    // "ACME Ltd.", "Acme Limited" and "acme" all end up as "acme"
+    let cleaned: String = name.to_lowercase()
+        .replace('&', " and ")
+        .chars()
+        .filter(|c| !matches!(c, '.' | '\'' | '’'))
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    let mut words: Vec<&str> = cleaned.split_whitespace().collect();
+    while words.len() > 1 && words.last().map(|word| LEGAL_SUFFIXES.contains(word)).unwrap_or(false) {
+        words.pop();
+    }
+
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+
+    fn company(name: &str) -> Company {
+        Company {
+            id: 0,
+            name: name.to_string(),
+            website: String::new(),
+            notes: String::new(),
+            rating: None,
+            blocked: false,
+            recruiter: false,
+            job_count: 0,
+        }
+    }
+
+    #[test]
+    fn company_names_are_normalised() {
+        let cases = [
+            ("ACME Ltd.", "acme"),
+            ("Acme Limited", "acme"),
+            ("acme", "acme"),
+            ("  Acme,  Inc. ", "acme"),
+            ("Smith & Co", "smith and"),
+            ("Smith and Co.", "smith and"),
+            ("Marks & Spencer", "marks and spencer"),
+            ("Marks and Spencer plc", "marks and spencer"),
+            ("O'Reilly Media", "oreilly media"),
+            ("Co", "co"),
+            ("Ltd.", "ltd"),
+            ("Inc Ltd", "inc"),
+            ("", ""),
+            (" & ", "and"),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(normalise_company(name), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn renamed_companies_keep_their_old_name_as_an_alias() {
+        let conn = test_connection();
+        let acme = link_company(&conn, "Acme Ltd").unwrap().unwrap();
+
+        save_company(&conn, acme, &company("Acme Robotics"), "acme robotics").unwrap();
+        // Jobs still posted under either name land on the same company
+        assert_eq!(link_company(&conn, "ACME Limited").unwrap(), Some(acme));
+        assert_eq!(link_company(&conn, "Acme Robotics Inc").unwrap(), Some(acme));
+
+        // Another company can't take the old name, and renaming back doesn't leave a stale alias
+        let other = link_company(&conn, "Globex").unwrap().unwrap();
+        assert_eq!(save_company(&conn, other, &company("Acme"), "acme").unwrap_err(), "Acme is already in your companies");
+        save_company(&conn, acme, &company("Acme"), "acme").unwrap();
+        let aliases: Vec<String> = conn.prepare("SELECT normalised_name FROM company_aliases").unwrap()
+            .query_map([], |row| row.get(0)).unwrap()
+            .collect::<Result<_, _>>().unwrap();
+        assert_eq!(aliases, vec!["acme robotics".to_string()]);
+        assert_eq!(link_company(&conn, "Acme Robotics").unwrap(), Some(acme));
+    }
+}
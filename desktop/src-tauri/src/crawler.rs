@@ -0,0 +1,351 @@
+use crate::schemas::CrawlPolicy;
+use crate::settings::{get_setting, set_setting};
+use crate::helpers::get_db_path;
+
+use reqwest::{Client, StatusCode, Url};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use log::{info, error};
+use tauri::{AppHandle, Manager};
+
+const BUDGET_WINDOW: Duration = Duration::from_secs(60 * 60);
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+const MAX_RETRIES: u32 = 10;
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+// Every job source goes through here, so limits hold however many searches are running
+#[derive(Default)]
+pub struct Crawler {
+    sources: Mutex<HashMap<String, SourceState>>,
+    robots: Mutex<HashMap<String, Robots>>,
+}
+
+#[derive(Default)]
+struct SourceState {
+    last_request: Option<Instant>,
+    last_run: Option<Instant>,
+    window_start: Option<Instant>,
+    requests_in_window: u32,
+    running: bool,
+}
+
+#[derive(Default, Clone)]
+struct Robots {
+    allow: Vec<String>,
+    disallow: Vec<String>,
+}
+
+pub struct Page {
+    pub url: String,
+    pub body: String,
+}
+
+pub struct RunGuard {
+    app_handle: AppHandle,
+    source: String,
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        let crawler = self.app_handle.state::<Crawler>();
+        let mut sources = crawler.sources.lock().unwrap();
+        if let Some(state) = sources.get_mut(&self.source) {
+            state.running = false;
+        }
+    }
+}
+
+#[tauri::command]
+pub fn get_crawl_policy(app_handle: AppHandle) -> Result<CrawlPolicy, String> {
+    load_policy(&app_handle)
+}
+
+#[tauri::command]
+pub fn set_crawl_policy(app_handle: AppHandle, mut policy: CrawlPolicy) -> Result<(), String> {
+    if policy.user_agent.trim().is_empty() {
+        return Err("The user agent can't be empty".to_string());
+    }
+    if policy.max_requests_per_hour == 0 {
+        return Err("Allow at least one request per hour".to_string());
+    }
+    policy.max_retries = policy.max_retries.min(MAX_RETRIES);
+    policy.backoff_ms = policy.backoff_ms.min(MAX_BACKOFF.as_millis() as u64);
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    set_setting(&conn, "crawl_policy", &policy)
+}
+
+pub fn load_policy(app_handle: &AppHandle) -> Result<CrawlPolicy, String> {
+    let conn = Connection::open(get_db_path(app_handle)).map_err(|e| e.to_string())?;
+    get_setting(&conn, "crawl_policy")
+}
+
+pub fn begin_run(app_handle: &AppHandle, source: &str, scheduled: bool) -> Result<RunGuard, String> {
+    // Search engines do their own HTTP, so they are limited per run rather than per request
+    let policy = load_policy(app_handle)?;
+    let crawler = app_handle.state::<Crawler>();
+    let mut sources = crawler.sources.lock().unwrap();
+    let state = sources.entry(source.to_string()).or_default();
+
+    if state.running {
+        return Err(format!("A {} search is already running", source));
+    }
+
+    // Saved searches queue up behind the scheduler lock, so back to back runs of one source are expected there
+    let min_interval = Duration::from_secs(policy.min_run_interval_minutes * 60);
+    if let Some(last_run) = state.last_run.filter(|_| !scheduled) {
+        let elapsed = last_run.elapsed();
+        if elapsed < min_interval {
+            return Err(format!(
+                "{} was searched {} seconds ago, try again in {} seconds",
+                source,
+                elapsed.as_secs(),
+                (min_interval - elapsed).as_secs() + 1
+            ));
+        }
+    }
+
+    take_budget(state, &policy, source)?;
+    state.last_run = Some(Instant::now());
+    state.running = true;
+
+    Ok(RunGuard {
+        app_handle: app_handle.clone(),
+        source: source.to_string(),
+    })
+}
+
+pub async fn fetch(app_handle: &AppHandle, source: &str, url: &str) -> Result<Page, String> {
+    let policy = load_policy(app_handle)?;
+    let crawler = app_handle.state::<Crawler>();
+    fetch_with(&crawler, &policy, source, url).await
+}
+
+pub async fn fetch_with(crawler: &Crawler, policy: &CrawlPolicy, source: &str, url: &str) -> Result<Page, String> {
+    let client = http_client(policy)?;
+
+    let parsed = Url::parse(url).map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+    check_robots(crawler, &client, &parsed, policy).await?;
+
+    let mut attempt = 0;
+    loop {
+        wait_turn(crawler, source, policy).await?;
+
+        let retry_after = match client.get(parsed.clone()).send().await {
+            Ok(res) if res.status().is_success() => {
+                let url = res.url().to_string();
+                let body = res.text().await.map_err(|e| e.to_string())?;
+                return Ok(Page { url, body });
+            }
+            Ok(res) if should_retry(res.status()) && attempt < policy.max_retries => {
+                info!("{} returned {}, retrying", url, res.status());
+                res.headers()
+                    .get("retry-after")
+                    .and_then(|value| value.to_str().ok())
+                    .and_then(|value| value.trim().parse::<u64>().ok())
+                    .map(|seconds| Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+            }
+            Ok(res) => {
+                let error_msg = format!("HTTP Error: {} while fetching {}", res.status(), url);
+                error!("{}", error_msg);
+                return Err(error_msg);
+            }
+            Err(e) if attempt < policy.max_retries => {
+                info!("Fetching {} failed ({}), retrying", url, e);
+                None
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+
+        tokio::time::sleep(retry_after.unwrap_or_else(|| retry_backoff(policy, attempt))).await;
+        attempt += 1;
+    }
+}
+
+pub fn http_client(policy: &CrawlPolicy) -> Result<Client, String> {
+    Client::builder()
+        .user_agent(&policy.user_agent)
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+pub async fn check_robots(crawler: &Crawler, client: &Client, url: &Url, policy: &CrawlPolicy) -> Result<(), String> {
+    if policy.respect_robots && !robots_allow(crawler, client, url, policy).await {
+        return Err(format!("robots.txt for {} doesn't allow fetching {}", url.host_str().unwrap_or_default(), url));
+    }
+    Ok(())
+}
+
+pub fn retry_backoff(policy: &CrawlPolicy, attempt: u32) -> Duration {
+    // Doubles with every attempt, capped so a large setting can't overflow or stall a search for hours
+    let factor = 2u64.checked_pow(attempt).unwrap_or(u64::MAX);
+    Duration::from_millis(policy.backoff_ms.saturating_mul(factor)).min(MAX_BACKOFF)
+}
+
+async fn wait_turn(crawler: &Crawler, source: &str, policy: &CrawlPolicy) -> Result<(), String> {
+    let delay = Duration::from_millis(policy.delay_ms);
+    loop {
+        let wait = {
+            let mut sources = crawler.sources.lock().unwrap();
+            let state = sources.entry(source.to_string()).or_default();
+
+            match state.last_request.map(|last| last.elapsed()) {
+                Some(elapsed) if elapsed < delay => delay - elapsed,
+                _ => {
+                    take_budget(state, policy, source)?;
+                    state.last_request = Some(Instant::now());
+                    return Ok(());
+                }
+            }
+        };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+fn take_budget(state: &mut SourceState, policy: &CrawlPolicy, source: &str) -> Result<(), String> {
+    let now = Instant::now();
+    match state.window_start {
+        Some(start) if now.duration_since(start) < BUDGET_WINDOW => {}
+        _ => {
+            state.window_start = Some(now);
+            state.requests_in_window = 0;
+        }
+    }
+
+    if state.requests_in_window >= policy.max_requests_per_hour {
+        let resets_in = BUDGET_WINDOW - now.duration_since(state.window_start.unwrap_or(now));
+        return Err(format!(
+            "The hourly request budget for {} is used up, try again in {} minutes",
+            source,
+            resets_in.as_secs() / 60 + 1
+        ));
+    }
+
+    state.requests_in_window += 1;
+    Ok(())
+}
+
+fn should_retry(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+async fn robots_allow(crawler: &Crawler, client: &Client, url: &Url, policy: &CrawlPolicy) -> bool {
+    let origin = url.origin().ascii_serialization();
+    let cached = crawler.robots.lock().unwrap().get(&origin).cloned();
+
+    let robots = match cached {
+        Some(robots) => robots,
+        None => {
+            // A missing or unreachable robots.txt means everything is allowed
+            let robots = match client.get(format!("{}/robots.txt", origin)).send().await {
+                Ok(res) if res.status().is_success() => {
+                    parse_robots(&res.text().await.unwrap_or_default(), &policy.user_agent)
+                }
+                _ => Robots::default(),
+            };
+            crawler.robots.lock().unwrap().insert(origin, robots.clone());
+            robots
+        }
+    };
+
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+
+    // The longest matching rule wins, with Allow breaking ties
+    let longest = |rules: &[String]| rules.iter()
+        .filter(|rule| path.starts_with(rule.as_str()))
+        .map(|rule| rule.len())
+        .max();
+
+    match (longest(&robots.allow), longest(&robots.disallow)) {
+        (_, None) => true,
+        (None, Some(_)) => false,
+        (Some(allow), Some(disallow)) => allow >= disallow,
+    }
+}
+
+fn parse_robots(text: &str, user_agent: &str) -> Robots {
+    let user_agent = user_agent.to_lowercase();
+    let mut specific = Robots::default();
+    let mut wildcard = Robots::default();
+    let mut found_specific = false;
+
+    let mut agents: Vec<String> = Vec::new();
+    let mut in_rules = false;
+
+    for line in text.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field.trim().to_lowercase(), value.trim()),
+            None => continue,
+        };
+
+        match field.as_str() {
+            "user-agent" => {
+                // A user-agent line after rules starts a new group
+                if in_rules {
+                    agents.clear();
+                    in_rules = false;
+                }
+                agents.push(value.to_lowercase());
+            }
+            "allow" | "disallow" if value.is_empty() => in_rules = true,
+            "allow" | "disallow" => {
+                in_rules = true;
+                for agent in &agents {
+                    let target = if agent == "*" {
+                        &mut wildcard
+                    } else if user_agent.contains(agent.as_str()) {
+                        found_specific = true;
+                        &mut specific
+                    } else {
+                        continue;
+                    };
+
+                    if field == "allow" {
+                        target.allow.push(value.to_string());
+                    } else {
+                        target.disallow.push(value.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if found_specific { specific } else { wildcard }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_saturates() {
+        let policy = CrawlPolicy { backoff_ms: 1000, ..Default::default() };
+        assert_eq!(retry_backoff(&policy, 0), Duration::from_secs(1));
+        assert_eq!(retry_backoff(&policy, 3), Duration::from_secs(8));
+        assert_eq!(retry_backoff(&policy, 64), MAX_BACKOFF);
+
+        let policy = CrawlPolicy { backoff_ms: u64::MAX, ..Default::default() };
+        assert_eq!(retry_backoff(&policy, 5), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn robots_prefers_the_matching_agent_group() {
+        let robots = parse_robots(
+            "User-agent: *\nDisallow: /\n\nUser-agent: Solicit\nAllow: /jobs\nDisallow: /jobs/private\n",
+            "Mozilla/5.0 (compatible; Solicit Jobhunter)",
+        );
+        assert_eq!(robots.allow, vec!["/jobs"]);
+        assert_eq!(robots.disallow, vec!["/jobs/private"]);
+
+        let robots = parse_robots("User-agent: *\nDisallow: /search # no crawling\n", "SomethingElse");
+        assert_eq!(robots.disallow, vec!["/search"]);
+    }
+}
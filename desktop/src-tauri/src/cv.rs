@@ -0,0 +1,329 @@
+use crate::schemas::{CvDetails, ExperienceEntry, EducationEntry, Project, Certificate, ParsedDetails};
+
+use serde_json::{Map, Value};
+use std::path::PathBuf;
+use log::error;
+use tauri::AppHandle;
+use std::fs;
+
+#[tauri::command]
+pub fn read_cv_details(app_handle: AppHandle) -> Result<CvDetails, String> {
+    let file_path = get_cv_details_path(&app_handle);
+
+    if !file_path.exists() {
+        return Ok(CvDetails::default());
+    }
+
+    let contents = fs::read_to_string(&file_path).map_err(|e| {
+        error!("Failed to read CV details: {}", e);
+        e.to_string()
+    })?;
+
+    serde_json::from_str(&contents).map_err(|e| {
+        error!("Failed to parse CV details: {}", e);
+        e.to_string()
+    })
+}
+
+#[tauri::command]
+pub fn write_cv_details(app_handle: AppHandle, details: CvDetails) -> Result<(), String> {
+    save_cv_details(&app_handle, &details)
+}
+
+#[tauri::command]
+pub fn flatten_cv_details(details: CvDetails) -> ParsedDetails {
+    ParsedDetails::from(&details)
+}
+
+pub fn save_cv_details(app_handle: &AppHandle, details: &CvDetails) -> Result<(), String> {
+    let file_path = get_cv_details_path(app_handle);
+    let content = serde_json::to_string_pretty(details).map_err(|e| e.to_string())?;
+
+    fs::write(&file_path, content).map_err(|e| {
+        error!("Failed to write CV details: {}", e);
+        e.to_string()
+    })
+}
+
+pub fn parse_backend_cv(backend_result: &Value) -> CvDetails {
+    // /api/cv returns Experience, Interests, Projects, Education and Certificates arrays,
+    // usually of plain strings, but objects per entry are accepted too
+    CvDetails {
+        experience: entries(backend_result, "Experience", |entry| match entry {
+            Value::Object(obj) => ExperienceEntry {
+                employer: text(obj, &["employer", "company", "organisation", "organization"]),
+                role: text(obj, &["role", "title", "position"]),
+                start_date: text(obj, &["start_date", "startDate", "start"]),
+                end_date: text(obj, &["end_date", "endDate", "end"]),
+                bullets: list(obj, &["bullets", "highlights", "responsibilities", "description"]),
+                ..Default::default()
+            },
+            other => ExperienceEntry { role: plain(other), ..Default::default() },
+        }),
+        interests: entries(backend_result, "Interests", |entry| match entry {
+            Value::Object(obj) => text(obj, &["name", "interest"]),
+            other => plain(other),
+        }),
+        projects: entries(backend_result, "Projects", |entry| match entry {
+            Value::Object(obj) => Project {
+                name: text(obj, &["name", "title"]),
+                description: text(obj, &["description", "summary"]),
+                url: text(obj, &["url", "link"]),
+                highlights: list(obj, &["highlights", "bullets", "technologies"]),
+            },
+            other => Project { name: plain(other), ..Default::default() },
+        }),
+        education: entries(backend_result, "Education", |entry| match entry {
+            Value::Object(obj) => EducationEntry {
+                degree: text(obj, &["degree", "qualification", "studyType", "area"]),
+                institution: text(obj, &["institution", "school", "university"]),
+                year: text(obj, &["year", "end_date", "endDate", "date"]),
+                ..Default::default()
+            },
+            other => EducationEntry { degree: plain(other), ..Default::default() },
+        }),
+        certificates: entries(backend_result, "Certificates", |entry| match entry {
+            Value::Object(obj) => Certificate {
+                name: text(obj, &["name", "title", "certificate"]),
+                issuer: text(obj, &["issuer", "authority", "organisation", "organization"]),
+                date: text(obj, &["date", "year"]),
+            },
+            other => Certificate { name: plain(other), ..Default::default() },
+        }),
+        ..Default::default()
+    }
+}
+
+impl From<&CvDetails> for ParsedDetails {
+    // Compatibility with the flat, newline-joined applicant_details.json format
+    fn from(details: &CvDetails) -> Self {
+        let experience = details.experience.iter().map(|entry| {
+            let mut lines = vec![with_dates(
+                join_present(&[&entry.role, &entry.employer], ", "),
+                &entry.start_date,
+                &entry.end_date,
+            )];
+            if !entry.summary.is_empty() {
+                lines.push(entry.summary.clone());
+            }
+            lines.extend(entry.bullets.iter().map(|bullet| format!("- {}", bullet)));
+            join_present_owned(lines, "\n")
+        });
+
+        let projects = details.projects.iter().map(|project| {
+            let mut line = join_present(&[&project.name, &project.description], ": ");
+            if !project.url.is_empty() {
+                line = format!("{} ({})", line, project.url);
+            }
+            let mut lines = vec![line];
+            lines.extend(project.highlights.iter().map(|highlight| format!("- {}", highlight)));
+            join_present_owned(lines, "\n")
+        });
+
+        let education = details.education.iter().map(|entry| {
+            with_dates(join_present(&[&entry.degree, &entry.area, &entry.institution], ", "), &entry.start_date, &entry.year)
+        });
+
+        let mut certificates: Vec<String> = details.certificates.iter().map(|certificate| {
+            with_dates(join_present(&[&certificate.name, &certificate.issuer], ", "), &certificate.date, "")
+        }).collect();
+
+        if !details.skills.is_empty() {
+            let skills = details.skills.iter()
+                .map(|skill| skill.name.as_str())
+                .filter(|name| !name.is_empty())
+                .collect::<Vec<_>>()
+                .join(", ");
+            certificates.push(format!("Skills: {}", skills));
+        }
+
+        ParsedDetails {
+            experience: join_present_owned(experience.collect(), "\n"),
+            interests: join_present_owned(details.interests.clone(), "\n"),
+            projects: join_present_owned(projects.collect(), "\n"),
+            education: join_present_owned(education.collect(), "\n"),
+            certificates: join_present_owned(certificates, "\n"),
+        }
+    }
+}
+
+fn entries<T>(backend_result: &Value, key: &str, parse: impl Fn(&Value) -> T) -> Vec<T> {
+    backend_result[key].as_array()
+        .map(|arr| arr.iter().map(parse).collect())
+        .unwrap_or_default()
+}
+
+fn text(obj: &Map<String, Value>, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| obj.get(*key))
+        .map(plain)
+        .unwrap_or_default()
+}
+
+fn list(obj: &Map<String, Value>, keys: &[&str]) -> Vec<String> {
+    match keys.iter().find_map(|key| obj.get(*key)) {
+        Some(Value::Array(arr)) => arr.iter().map(plain).filter(|s| !s.is_empty()).collect(),
+        Some(Value::String(s)) => s.lines().map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn plain(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn join_present(parts: &[&String], separator: &str) -> String {
+    parts.iter()
+        .filter(|part| !part.is_empty())
+        .map(|part| part.as_str())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn join_present_owned(parts: Vec<String>, separator: &str) -> String {
+    parts.into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn with_dates(line: String, start: &str, end: &str) -> String {
+    let dates = match (start.is_empty(), end.is_empty()) {
+        (true, true) => return line,
+        (false, true) => start.to_string(),
+        (true, false) => end.to_string(),
+        (false, false) => format!("{} - {}", start, end),
+    };
+
+    if line.is_empty() {
+        dates
+    } else {
+        format!("{} ({})", line, dates)
+    }
+}
+
+fn get_cv_details_path(app_handle: &AppHandle) -> PathBuf {
+    // Helper function
+    app_handle.path_resolver()
+        .app_data_dir()
+        .expect("Failed to get app data dir")
+        .join("cv_details.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Skill;
+    use serde_json::json;
+
+    fn backend_payload() -> Value {
+        // The shape /api/cv responds with
+        json!({
+            "Experience": [
+                "Senior Engineer, Analytical Engines Ltd (2019 - 2023)",
+                "Developer, Acme (2016 - 2019)"
+            ],
+            "Interests": ["Chess", "Climbing"],
+            "Projects": ["notes: Markdown note taker"],
+            "Education": ["BSc Mathematics, University of London (2015)"],
+            "Certificates": ["AWS Solutions Architect"]
+        })
+    }
+
+    #[test]
+    fn parses_backend_string_entries() {
+        let details = parse_backend_cv(&backend_payload());
+
+        assert_eq!(details.experience.len(), 2);
+        assert_eq!(details.experience[0].role, "Senior Engineer, Analytical Engines Ltd (2019 - 2023)");
+        assert_eq!(details.interests, vec!["Chess".to_string(), "Climbing".to_string()]);
+        assert_eq!(details.projects[0].name, "notes: Markdown note taker");
+        assert_eq!(details.education[0].degree, "BSc Mathematics, University of London (2015)");
+        assert_eq!(details.certificates[0].name, "AWS Solutions Architect");
+        assert!(details.name.is_empty());
+        assert!(details.skills.is_empty());
+    }
+
+    #[test]
+    fn parses_backend_object_entries() {
+        let details = parse_backend_cv(&json!({
+            "Experience": [{"company": "Acme", "title": "Developer", "startDate": "2016", "endDate": "2019",
+                "highlights": ["Shipped the billing system"]}],
+            "Education": [{"degree": "MSc", "school": "UCL", "year": 2016}],
+            "Certificates": [{"name": "CKA", "issuer": "CNCF", "date": "2022"}]
+        }));
+
+        assert_eq!(details.experience[0].employer, "Acme");
+        assert_eq!(details.experience[0].role, "Developer");
+        assert_eq!(details.experience[0].bullets, vec!["Shipped the billing system".to_string()]);
+        assert_eq!(details.education[0].institution, "UCL");
+        assert_eq!(details.education[0].year, "2016");
+        assert_eq!(details.certificates[0].issuer, "CNCF");
+    }
+
+    #[test]
+    fn missing_backend_sections_are_empty() {
+        let details = parse_backend_cv(&json!({"Experience": null}));
+        assert_eq!(details, CvDetails::default());
+    }
+
+    #[test]
+    fn backend_strings_flatten_back_to_the_old_format() {
+        // Plain string entries must come out exactly as the old newline-joined extract_cv did
+        let flat = ParsedDetails::from(&parse_backend_cv(&backend_payload()));
+
+        assert_eq!(flat.experience, "Senior Engineer, Analytical Engines Ltd (2019 - 2023)\nDeveloper, Acme (2016 - 2019)");
+        assert_eq!(flat.interests, "Chess\nClimbing");
+        assert_eq!(flat.projects, "notes: Markdown note taker");
+        assert_eq!(flat.education, "BSc Mathematics, University of London (2015)");
+        assert_eq!(flat.certificates, "AWS Solutions Architect");
+    }
+
+    #[test]
+    fn flattens_structured_entries() {
+        let details = CvDetails {
+            experience: vec![ExperienceEntry {
+                employer: "Acme".to_string(),
+                role: "Developer".to_string(),
+                start_date: "2016".to_string(),
+                end_date: "2019".to_string(),
+                summary: String::new(),
+                bullets: vec!["Shipped".to_string()],
+            }],
+            projects: vec![Project {
+                name: "notes".to_string(),
+                description: "Note taker".to_string(),
+                url: "https://example.com".to_string(),
+                highlights: Vec::new(),
+            }],
+            education: vec![EducationEntry {
+                degree: "BSc".to_string(),
+                area: "Mathematics".to_string(),
+                institution: "UCL".to_string(),
+                start_date: "2012".to_string(),
+                year: "2015".to_string(),
+            }],
+            certificates: vec![Certificate {
+                name: "CKA".to_string(),
+                issuer: "CNCF".to_string(),
+                date: "2022".to_string(),
+            }],
+            skills: vec![
+                Skill { name: "Rust".to_string(), ..Default::default() },
+                Skill { name: "SQL".to_string(), ..Default::default() },
+            ],
+            ..Default::default()
+        };
+        let flat = ParsedDetails::from(&details);
+
+        assert_eq!(flat.experience, "Developer, Acme (2016 - 2019)\n- Shipped");
+        assert_eq!(flat.projects, "notes: Note taker (https://example.com)");
+        assert_eq!(flat.education, "BSc, Mathematics, UCL (2012 - 2015)");
+        assert_eq!(flat.certificates, "CKA, CNCF (2022)\nSkills: Rust, SQL");
+        assert_eq!(flat.interests, "");
+    }
+}
@@ -0,0 +1,244 @@
+use rusqlite::Connection;
+use log::info;
+
+pub fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    // The bundled insegnante.sqlite only ships the original jobs and stats tables,
+    // so anything added since is created here on startup
+    add_columns(conn, "jobs", &[
+        ("match_score", "REAL"),
+        ("matched_skills", "TEXT"),
+        ("missing_skills", "TEXT"),
+        ("description", "TEXT"),
+        ("requirements", "TEXT"),
+        ("apply_url", "TEXT"),
+        ("extracted_requirements", "TEXT"),
+        ("salary_min", "REAL"),
+        ("salary_max", "REAL"),
+        ("salary_currency", "TEXT"),
+        ("salary_period", "TEXT"),
+        ("salary_annual", "REAL"),
+        ("processed", "INTEGER NOT NULL DEFAULT 0"),
+        ("hidden", "INTEGER NOT NULL DEFAULT 0"),
+        ("archived_at", "TEXT"),
+    ])?;
+
+    let location_added = add_columns(conn, "jobs", &[
+        ("city", "TEXT"),
+        ("region", "TEXT"),
+        ("country", "TEXT"),
+        ("work_mode", "TEXT"),
+    ])?;
+
+    let company_added = add_columns(conn, "jobs", &[
+        ("company_id", "INTEGER"),
+    ])?;
+
+    if location_added || company_added {
+        // Derived columns were added, so existing jobs need processing again
+        conn.execute("UPDATE jobs SET processed = 0", [])?;
+    }
+
+    let has_history: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type = 'table' AND name = 'job_history'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_history {
+        // Older rows only know they were fetched and maybe applied to, so that is all the history they get
+        conn.execute_batch(
+            "CREATE TABLE job_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                job_id INTEGER NOT NULL,
+                status TEXT NOT NULL,
+                changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+            CREATE INDEX job_history_job ON job_history (job_id);
+            INSERT INTO job_history (job_id, status, changed_at)
+                SELECT id, 'added', COALESCE(fetched_date, datetime('now')) FROM jobs;
+            INSERT INTO job_history (job_id, status, changed_at)
+                SELECT id, 'applied', COALESCE(fetched_date, datetime('now')) FROM jobs WHERE appliedto = 1;"
+        )?;
+    }
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS saved_searches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            keywords TEXT NOT NULL,
+            location TEXT NOT NULL DEFAULT '',
+            sources TEXT NOT NULL DEFAULT '[]',
+            filters TEXT NOT NULL DEFAULT '{}',
+            interval_minutes INTEGER NOT NULL DEFAULT 60,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            last_run TEXT,
+            last_result TEXT,
+            last_new_jobs INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE TABLE IF NOT EXISTS search_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source TEXT NOT NULL,
+            keywords TEXT NOT NULL,
+            location TEXT NOT NULL DEFAULT '',
+            run_at TEXT NOT NULL DEFAULT (datetime('now')),
+            duration_ms INTEGER NOT NULL DEFAULT 0,
+            pages_fetched INTEGER NOT NULL DEFAULT 0,
+            jobs_seen INTEGER NOT NULL DEFAULT 0,
+            inserted INTEGER NOT NULL DEFAULT 0,
+            duplicates_skipped INTEGER NOT NULL DEFAULT 0,
+            errors TEXT NOT NULL DEFAULT '[]'
+        );
+        CREATE TABLE IF NOT EXISTS feeds (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            url TEXT NOT NULL,
+            enabled INTEGER NOT NULL DEFAULT 1,
+            last_fetched TEXT,
+            last_error TEXT
+        );
+        CREATE TABLE IF NOT EXISTS job_notes (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id INTEGER NOT NULL,
+            body TEXT NOT NULL,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS job_notes_job ON job_notes (job_id);
+        CREATE TABLE IF NOT EXISTS tags (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE COLLATE NOCASE
+        );
+        CREATE TABLE IF NOT EXISTS job_tags (
+            job_id INTEGER NOT NULL,
+            tag_id INTEGER NOT NULL,
+            PRIMARY KEY (job_id, tag_id)
+        );
+        CREATE TABLE IF NOT EXISTS job_contacts (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id INTEGER NOT NULL,
+            name TEXT NOT NULL,
+            email TEXT NOT NULL DEFAULT '',
+            role TEXT NOT NULL DEFAULT ''
+        );
+        CREATE INDEX IF NOT EXISTS job_contacts_job ON job_contacts (job_id);
+        CREATE TABLE IF NOT EXISTS companies (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            normalised_name TEXT NOT NULL UNIQUE,
+            website TEXT NOT NULL DEFAULT '',
+            notes TEXT NOT NULL DEFAULT '',
+            rating INTEGER,
+            blocked INTEGER NOT NULL DEFAULT 0,
+            recruiter INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE TABLE IF NOT EXISTS purged_uniqueids (
+            uniqueid TEXT PRIMARY KEY,
+            purged_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE TABLE IF NOT EXISTS company_aliases (
+            normalised_name TEXT PRIMARY KEY,
+            company_id INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS job_rules (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL DEFAULT '',
+            field TEXT NOT NULL DEFAULT 'title',
+            kind TEXT NOT NULL,
+            pattern TEXT NOT NULL DEFAULT '',
+            min_salary REAL,
+            mode TEXT NOT NULL DEFAULT 'exclude',
+            action TEXT NOT NULL,
+            tag TEXT NOT NULL DEFAULT '',
+            enabled INTEGER NOT NULL DEFAULT 1
+        );
+        CREATE TABLE IF NOT EXISTS reminders (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            job_id INTEGER NOT NULL,
+            kind TEXT NOT NULL,
+            title TEXT NOT NULL,
+            due_at TEXT NOT NULL,
+            duration_minutes INTEGER NOT NULL DEFAULT 0,
+            location TEXT NOT NULL DEFAULT '',
+            link TEXT NOT NULL DEFAULT '',
+            notes TEXT NOT NULL DEFAULT '',
+            notify_before_minutes INTEGER NOT NULL DEFAULT 0,
+            notified_at TEXT,
+            done INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL DEFAULT (datetime('now')),
+            updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+        );
+        CREATE INDEX IF NOT EXISTS reminders_job ON reminders (job_id);
+        CREATE INDEX IF NOT EXISTS reminders_due ON reminders (due_at);
+        CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );"
+    )?;
+    add_columns(conn, "saved_searches", &[
+        ("notify", "INTEGER NOT NULL DEFAULT 1"),
+    ])?;
+    add_columns(conn, "reminders", &[
+        ("sequence", "INTEGER NOT NULL DEFAULT 0"),
+    ])?;
+
+    Ok(())
+}
+
+fn add_columns(conn: &Connection, table: &str, columns: &[(&str, &str)]) -> rusqlite::Result<bool> {
+    let existing = conn.prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut added = false;
+    for (name, definition) in columns {
+        if !existing.iter().any(|column| column == name) {
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, name, definition), [])?;
+            info!("Added column {}.{}", table, name);
+            added = true;
+        }
+    }
+
+    Ok(added)
+}
+
+#[cfg(test)]
+pub fn test_connection() -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    create_test_tables(&conn);
+    conn
+}
+
+#[cfg(test)]
+pub fn test_database_path(name: &str) -> std::path::PathBuf {
+    // For code that opens the database by path itself, one file per test so they can run in parallel
+    let path = std::env::temp_dir().join(format!("solicit-test-{}-{}.sqlite", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    create_test_tables(&Connection::open(&path).unwrap());
+    path
+}
+
+#[cfg(test)]
+fn create_test_tables(conn: &Connection) {
+    // The bundled database's original tables, migrated the same way the app does on startup
+    conn.execute_batch(
+        "CREATE TABLE jobs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            uniqueid TEXT UNIQUE,
+            title TEXT,
+            company TEXT,
+            location TEXT,
+            salary TEXT,
+            jobkey TEXT,
+            fetched_date TEXT,
+            read INTEGER NOT NULL DEFAULT 0,
+            appliedto INTEGER NOT NULL DEFAULT 0,
+            source TEXT
+        );
+        CREATE TABLE stats (
+            id INTEGER PRIMARY KEY,
+            uniquejobs INTEGER NOT NULL DEFAULT 0,
+            appliedjobs INTEGER NOT NULL DEFAULT 0
+        );
+        INSERT INTO stats (id, uniquejobs, appliedjobs) VALUES (1, 0, 0);"
+    ).unwrap();
+    migrate(conn).unwrap();
+}
@@ -0,0 +1,350 @@
+use crate::schemas::{Feed, NewJob, SearchProgress, SearchReport};
+use crate::crawler::fetch;
+use crate::html::html_to_text;
+use crate::ingest::{insert_job, matches_query, process_new_jobs};
+use crate::jobsearch::{before_deadline, emit_progress};
+use crate::helpers::get_db_path;
+
+use reqwest::Url;
+use rusqlite::{params, Connection};
+use std::fs;
+use std::time::Instant;
+use log::{info, error};
+use tauri::AppHandle;
+
+const FEED_COLUMNS: &str = "id, name, url, enabled, last_fetched, last_error";
+
+#[tauri::command]
+pub fn list_feeds(app_handle: AppHandle) -> Result<Vec<Feed>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM feeds ORDER BY name", FEED_COLUMNS))
+        .map_err(|e| e.to_string())?;
+
+    let feeds = stmt.query_map([], feed_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(feeds)
+}
+
+#[tauri::command]
+pub fn add_feed(app_handle: AppHandle, name: String, url: String) -> Result<Feed, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Please give the feed a name".to_string());
+    }
+    if ["indeed", "jooble", "feeds", "greenhouse", "lever", "manual", "url"].contains(&name.to_lowercase().as_str()) {
+        return Err(format!("{} is already used as a job source name", name));
+    }
+    Url::parse(url.trim()).map_err(|e| format!("Invalid feed URL: {}", e))?;
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO feeds (name, url) VALUES (?1, ?2)",
+        params![name, url.trim()],
+    ).map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM feeds WHERE id = ?1", FEED_COLUMNS),
+        [conn.last_insert_rowid()],
+        feed_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn set_feed_enabled(app_handle: AppHandle, feed_id: i32, enabled: bool) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("UPDATE feeds SET enabled = ?1 WHERE id = ?2", params![enabled, feed_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_feed(app_handle: AppHandle, feed_id: i32) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM feeds WHERE id = ?1", [feed_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn feed_from_row(row: &rusqlite::Row) -> rusqlite::Result<Feed> {
+    Ok(Feed {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        url: row.get(2)?,
+        enabled: row.get(3)?,
+        last_fetched: row.get(4)?,
+        last_error: row.get(5)?,
+    })
+}
+
+pub async fn run_feeds(app_handle: &AppHandle, report: &mut SearchReport, deadline: Instant) -> Vec<i32> {
+    let feeds = match list_feeds(app_handle.clone()) {
+        Ok(feeds) => feeds.into_iter().filter(|feed| feed.enabled).collect::<Vec<_>>(),
+        Err(e) => {
+            report.errors.push(e);
+            return Vec::new();
+        }
+    };
+
+    let mut new_job_ids = Vec::new();
+    for (i, feed) in feeds.iter().enumerate() {
+        emit_progress(app_handle, &SearchProgress {
+            source: report.source.clone(),
+            page: i as i32 + 1,
+            total_pages: Some(feeds.len() as i32),
+            jobs_found: report.jobs_seen,
+            message: format!("Reading {}", feed.name),
+        });
+
+        let result = match before_deadline(deadline, read_feed(app_handle, &report.source, &feed.url)).await {
+            Ok(Ok(xml)) => Connection::open(get_db_path(app_handle))
+                .map_err(|e| e.to_string())
+                .and_then(|conn| store_entries(&conn, &feed.name, &xml, report)),
+            Ok(Err(e)) => Err(e),
+            Err(e) => {
+                // Out of time, the feeds not read yet can wait for the next run
+                report.errors.push(format!("{}: {}", feed.name, e));
+                break;
+            }
+        };
+        report.pages_fetched += 1;
+
+        let last_error = match result {
+            Ok(ids) => {
+                new_job_ids.extend(ids);
+                None
+            }
+            Err(e) => {
+                error!("Failed to read feed {}: {}", feed.name, e);
+                report.errors.push(format!("{}: {}", feed.name, e));
+                Some(e)
+            }
+        };
+
+        if let Err(e) = Connection::open(get_db_path(app_handle)).and_then(|conn| conn.execute(
+            "UPDATE feeds SET last_fetched = datetime('now'), last_error = ?1 WHERE id = ?2",
+            params![last_error, feed.id],
+        )) {
+            error!("Failed to update feed {}: {}", feed.name, e);
+        }
+    }
+
+    new_job_ids
+}
+
+async fn read_feed(app_handle: &AppHandle, source: &str, url: &str) -> Result<String, String> {
+    // file:// URLs make it easy to point a feed at a saved copy
+    let parsed = Url::parse(url).map_err(|e| e.to_string())?;
+    if parsed.scheme() == "file" {
+        let path = parsed.to_file_path().map_err(|_| format!("Invalid file URL {}", url))?;
+        return fs::read_to_string(path).map_err(|e| e.to_string());
+    }
+
+    Ok(fetch(app_handle, source, url).await?.body)
+}
+
+fn store_entries(conn: &Connection, feed_name: &str, xml: &str, report: &mut SearchReport) -> Result<Vec<i32>, String> {
+    let jobs: Vec<NewJob> = parse_feed(xml, feed_name)?
+        .into_iter()
+        .filter(|job| matches_query(job, &report.keywords, &report.location))
+        .collect();
+    report.jobs_seen += jobs.len() as i32;
+
+    for job in &jobs {
+        insert_job(conn, job).map_err(|e| e.to_string())?;
+    }
+
+    let new_job_ids: Vec<i32> = process_new_jobs(conn, Some(feed_name))
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(id, _)| id)
+        .collect();
+    info!("Feed {} had {} matching entries, {} new", feed_name, jobs.len(), new_job_ids.len());
+
+    Ok(new_job_ids)
+}
+
+pub fn parse_feed(xml: &str, feed_name: &str) -> Result<Vec<NewJob>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("Not a valid feed: {}", e))?;
+
+    // RSS 2.0 and RSS 1.0 use <item>, Atom uses <entry>
+    let jobs = doc.descendants()
+        .filter(|node| node.is_element() && matches!(node.tag_name().name(), "item" | "entry"))
+        .filter_map(|entry| entry_to_job(entry, feed_name))
+        .collect();
+
+    Ok(jobs)
+}
+
+fn entry_to_job(entry: roxmltree::Node, feed_name: &str) -> Option<NewJob> {
+    let title = child_text(entry, &["title"])?;
+
+    let link = entry.children()
+        .filter(|node| node.is_element() && node.tag_name().name() == "link")
+        .find(|node| matches!(node.attribute("rel"), None | Some("alternate")))
+        .and_then(|node| node.attribute("href").map(str::to_string).or_else(|| node.text().map(|t| t.trim().to_string())))
+        .or_else(|| entry.attribute(("http://www.w3.org/1999/02/22-rdf-syntax-ns#", "about")).map(str::to_string))
+        .unwrap_or_default();
+
+    // Entries are deduplicated on their GUID, falling back to the link for feeds without one.
+    // GUIDs are often short numbers, so they are namespaced by feed like the ATS sources do
+    let guid = child_text(entry, &["guid", "id"]).unwrap_or_else(|| link.clone());
+    if guid.is_empty() {
+        return None;
+    }
+
+    let (title, company_from_title) = split_title(&title);
+    let company = child_text(entry, &["company", "hiringOrganization", "creator", "author"])
+        .or(company_from_title)
+        .unwrap_or_else(|| feed_name.to_string());
+
+    let description = child_text(entry, &["encoded", "content", "description", "summary"])
+        .map(|html| html_to_text(&html))
+        .unwrap_or_default();
+
+    Some(NewJob {
+        uniqueid: format!("feed:{}:{}", feed_name, guid),
+        title,
+        company,
+        location: child_text(entry, &["location", "region"]).unwrap_or_default(),
+        salary: child_text(entry, &["salary"]).unwrap_or_default(),
+        jobkey: link.clone(),
+        source: feed_name.to_string(),
+        description,
+        apply_url: link,
+        ..Default::default()
+    })
+}
+
+fn child_text(entry: roxmltree::Node, names: &[&str]) -> Option<String> {
+    // Matched on local name so namespaced fields like <job:location> or <dc:creator> are picked up
+    names.iter().find_map(|name| {
+        entry.children()
+            .find(|node| node.is_element() && node.tag_name().name() == *name)
+            .map(|node| {
+                // Atom authors wrap the name in a child element
+                let text: String = node.descendants().filter(|n| n.is_text()).filter_map(|n| n.text()).collect();
+                text.trim().to_string()
+            })
+            .filter(|text| !text.is_empty())
+    })
+}
+
+fn split_title(title: &str) -> (String, Option<String>) {
+    // Boards often title entries "Backend Developer at Acme"
+    match title.rsplit_once(" at ") {
+        Some((role, company)) if !role.trim().is_empty() && !company.trim().is_empty() => {
+            (role.trim().to_string(), Some(company.trim().to_string()))
+        }
+        _ => (title.to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+
+    const RSS2: &str = include_str!("../tests/fixtures/feeds/rss2.xml");
+    const ATOM: &str = include_str!("../tests/fixtures/feeds/atom.xml");
+    const RSS1: &str = include_str!("../tests/fixtures/feeds/rss1.xml");
+
+    fn report(keywords: &str, location: &str) -> SearchReport {
+        SearchReport {
+            source: "feeds".to_string(),
+            keywords: keywords.to_string(),
+            location: location.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_rss2_items() {
+        let jobs = parse_feed(RSS2, "Example").unwrap();
+        assert_eq!(jobs.len(), 2);
+
+        assert_eq!(jobs[0].uniqueid, "feed:Example:example-1001");
+        assert_eq!(jobs[0].title, "Senior Rust Developer");
+        assert_eq!(jobs[0].company, "Ferris Labs");
+        assert_eq!(jobs[0].location, "Remote, Europe");
+        assert_eq!(jobs[0].salary, "€70,000 - €85,000 per year");
+        assert_eq!(jobs[0].description, "Build services in Rust.\n- Tokio\n- PostgreSQL");
+        assert_eq!(jobs[0].apply_url, "https://jobs.example.com/rust-developer");
+        assert_eq!(jobs[0].source, "Example");
+
+        assert_eq!(jobs[1].company, "Snake Analytics");
+        assert_eq!(jobs[1].description, "Pipelines in Python and Airflow.");
+    }
+
+    #[test]
+    fn parses_atom_entries() {
+        let jobs = parse_feed(ATOM, "Atom").unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].uniqueid, "feed:Atom:urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a");
+        assert_eq!(jobs[0].company, "Crab Systems");
+        assert_eq!(jobs[0].apply_url, "https://atom.example.com/jobs/42");
+        assert_eq!(jobs[0].description, "Rust and gRPC services, London or remote.");
+    }
+
+    #[test]
+    fn parses_rss1_items_without_a_guid() {
+        let jobs = parse_feed(RSS1, "RDF").unwrap();
+        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs[0].uniqueid, "feed:RDF:https://rdf.example.com/jobs/7");
+        assert_eq!(jobs[0].company, "Svelte Studio");
+        assert_eq!(jobs[0].title, "Frontend Developer");
+    }
+
+    #[test]
+    fn rejects_invalid_xml() {
+        assert!(parse_feed("<rss><channel>", "Broken").is_err());
+    }
+
+    #[test]
+    fn entries_are_deduplicated_on_guid() {
+        let conn = test_connection();
+
+        let first = store_entries(&conn, "Example", RSS2, &mut report("", "")).unwrap();
+        assert_eq!(first.len(), 2);
+
+        let mut second_report = report("", "");
+        let second = store_entries(&conn, "Example", RSS2, &mut second_report).unwrap();
+        assert!(second.is_empty());
+        assert_eq!(second_report.jobs_seen, 2);
+
+        let stored: i32 = conn.query_row("SELECT COUNT(*) FROM jobs", [], |row| row.get(0)).unwrap();
+        assert_eq!(stored, 2);
+    }
+
+    #[test]
+    fn the_same_guid_in_two_feeds_is_two_jobs() {
+        let conn = test_connection();
+
+        let first = store_entries(&conn, "Example", RSS2, &mut report("", "")).unwrap();
+        let second = store_entries(&conn, "Mirror", RSS2, &mut report("", "")).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 2);
+    }
+
+    #[test]
+    fn only_entries_matching_the_query_are_stored() {
+        let conn = test_connection();
+
+        let mut rust_report = report("rust", "");
+        let stored = store_entries(&conn, "Example", RSS2, &mut rust_report).unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(rust_report.jobs_seen, 1);
+
+        let stored = store_entries(&conn, "Example", RSS2, &mut report("engineer", "milano")).unwrap();
+        assert_eq!(stored.len(), 1);
+        let title: String = conn.query_row("SELECT title FROM jobs WHERE id = ?1", [stored[0]], |row| row.get(0)).unwrap();
+        assert_eq!(title, "Python Data Engineer");
+
+        // Remote listings count wherever the search is
+        let mut remote_report = report("tokio", "torino");
+        store_entries(&conn, "Example", RSS2, &mut remote_report).unwrap();
+        assert_eq!(remote_report.jobs_seen, 1);
+    }
+}
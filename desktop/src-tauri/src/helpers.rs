@@ -26,7 +26,7 @@ fn get_system_resource_path(app_handle: &AppHandle, resource: &str) -> PathBuf {
     */
     app_handle.path_resolver()
         .resolve_resource(format!("resources/{}", resource))
-        .expect(&format!("failed to resolve resource {}", resource))
+        .unwrap_or_else(|| panic!("failed to resolve resource {}", resource))
 }
 
 fn get_user_resource_path(app_handle: &AppHandle, resource: &str) -> PathBuf {
@@ -0,0 +1,289 @@
+use serde_json::Value;
+
+const BLOCK_TAGS: &[&str] = &[
+    "p", "div", "br", "li", "ul", "ol", "tr", "table", "section", "article", "header", "footer",
+    "h1", "h2", "h3", "h4", "h5", "h6", "blockquote", "pre", "hr",
+];
+
+const INLINE_TAGS: &[&str] = &["a", "b", "i", "u", "em", "strong", "span", "small", "sup", "sub", "font", "mark"];
+
+pub fn html_to_text(html: &str) -> String {
+    if !html.contains('<') && html.contains("&lt;") {
+        // Feeds and JSON APIs often ship the markup entity-escaped
+        return html_to_text(&decode_entities(html));
+    }
+
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        if let Some(comment) = after.strip_prefix("!--") {
+            rest = comment.find("-->").map(|end| &comment[end + 3..]).unwrap_or("");
+            continue;
+        }
+
+        let end = match after.find('>') {
+            Some(end) => end,
+            None => {
+                // Unterminated tag, keep it as literal text
+                text.push_str(&rest[start..]);
+                rest = "";
+                break;
+            }
+        };
+
+        let tag = &after[..end];
+        let closing = tag.starts_with('/');
+        let name = tag.trim_start_matches('/')
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        rest = &after[end + 1..];
+
+        if !closing && (name == "script" || name == "style") {
+            let close = format!("</{}", name);
+            rest = match rest.to_ascii_lowercase().find(&close) {
+                Some(pos) => rest[pos..].find('>').map(|gt| &rest[pos + gt + 1..]).unwrap_or(""),
+                None => "",
+            };
+            continue;
+        }
+
+        if name == "li" && !closing {
+            text.push_str("\n- ");
+        } else if BLOCK_TAGS.contains(&name.as_str()) {
+            text.push('\n');
+        } else if !INLINE_TAGS.contains(&name.as_str()) {
+            text.push(' ');
+        }
+    }
+    text.push_str(rest);
+
+    decode_entities(&text)
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty() && line != "-")
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        let entity = after.find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&after[..end]).map(|c| (c, end)));
+
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &after[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = after;
+            }
+        }
+    }
+    decoded.push_str(rest);
+
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+        return u32::from_str_radix(hex, 16).ok().and_then(char::from_u32);
+    }
+    if let Some(dec) = entity.strip_prefix('#') {
+        return dec.parse().ok().and_then(char::from_u32);
+    }
+
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        "ndash" => Some('–'),
+        "mdash" => Some('—'),
+        "hellip" => Some('…'),
+        "rsquo" => Some('’'),
+        "lsquo" => Some('‘'),
+        "bull" => Some('•'),
+        "euro" => Some('€'),
+        "pound" => Some('£'),
+        _ => None,
+    }
+}
+
+pub fn find_job_posting(html: &str) -> Option<Value> {
+    // Most boards embed schema.org JobPosting data as JSON-LD for search engines
+    let lower = html.to_ascii_lowercase();
+    let mut offset = 0;
+
+    while let Some(pos) = lower[offset..].find("application/ld+json") {
+        let start = offset + pos;
+        let body_start = start + lower[start..].find('>')? + 1;
+        let body_end = body_start + lower[body_start..].find("</script")?;
+        offset = body_end;
+
+        if let Ok(value) = serde_json::from_str::<Value>(html[body_start..body_end].trim()) {
+            if let Some(posting) = job_posting_in(&value) {
+                return Some(posting.clone());
+            }
+        }
+    }
+
+    None
+}
+
+fn job_posting_in(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(job_posting_in),
+        Value::Object(obj) => {
+            let is_posting = match obj.get("@type") {
+                Some(Value::String(t)) => t == "JobPosting",
+                Some(Value::Array(types)) => types.iter().any(|t| t == "JobPosting"),
+                _ => false,
+            };
+            if is_posting {
+                Some(value)
+            } else {
+                obj.get("@graph").and_then(job_posting_in)
+            }
+        }
+        _ => None,
+    }
+}
+
+pub fn meta_content(html: &str, name: &str) -> Option<String> {
+    // Matches <meta property="og:title" content="..."> and <meta name="description" ...> in either attribute order
+    let lower = html.to_ascii_lowercase();
+    let mut offset = 0;
+
+    while let Some(pos) = lower[offset..].find("<meta") {
+        let start = offset + pos;
+        let end = start + lower[start..].find('>')?;
+        offset = end;
+
+        let tag = &html[start..end];
+        let key = attribute(tag, "property").or_else(|| attribute(tag, "name"));
+        if key.map(|key| key.eq_ignore_ascii_case(name)).unwrap_or(false) {
+            return attribute(tag, "content")
+                .map(|content| decode_entities(content).trim().to_string())
+                .filter(|content| !content.is_empty());
+        }
+    }
+
+    None
+}
+
+pub fn page_title(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let start = lower.find("<title")?;
+    let content_start = start + lower[start..].find('>')? + 1;
+    let content_end = content_start + lower[content_start..].find("</title")?;
+
+    Some(decode_entities(html[content_start..content_end].trim())).filter(|title| !title.is_empty())
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut offset = 0;
+
+    while let Some(pos) = lower[offset..].find(name) {
+        let start = offset + pos;
+        offset = start + name.len();
+
+        // Skip matches inside other attribute names, e.g. "name" in "data-name"
+        let before = lower[..start].chars().next_back();
+        if !before.map(|c| c.is_whitespace()).unwrap_or(false) {
+            continue;
+        }
+
+        let rest = tag[offset..].trim_start();
+        let rest = match rest.strip_prefix('=') {
+            Some(rest) => rest.trim_start(),
+            None => continue,
+        };
+        let quote = rest.chars().next()?;
+        if quote == '"' || quote == '\'' {
+            let value = &rest[1..];
+            return value.find(quote).map(|end| &value[..end]);
+        }
+        return rest.split(|c: char| c.is_whitespace() || c == '/').next();
+    }
+
+    None
+}
+
+pub fn element_inner_html<'a>(html: &'a str, id: &str) -> Option<&'a str> {
+    // Returns the markup inside the element with the given id, balancing nested tags of the same name
+    let marker_pos = [format!("id=\"{}\"", id), format!("id='{}'", id)].iter()
+        .find_map(|marker| html.find(marker.as_str()))?;
+    let open_start = html[..marker_pos].rfind('<')?;
+    inner_html_at(html, open_start)
+}
+
+pub fn tag_inner_html<'a>(html: &'a str, tag: &str) -> Option<&'a str> {
+    // Same as element_inner_html but for the first element of a kind, e.g. <main>
+    let lower = html.to_ascii_lowercase();
+    let open_tag = format!("<{}", tag);
+    let mut offset = 0;
+
+    while let Some(pos) = lower[offset..].find(&open_tag) {
+        let start = offset + pos;
+        offset = start + open_tag.len();
+        // Skip longer tag names that share the prefix, e.g. <mainframe>
+        if lower[offset..].starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+            return inner_html_at(html, start);
+        }
+    }
+
+    None
+}
+
+fn inner_html_at(html: &str, open_start: usize) -> Option<&str> {
+    let name = html[open_start + 1..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    let content_start = open_start + html[open_start..].find('>')? + 1;
+
+    let lower = html.to_ascii_lowercase();
+    let open_tag = format!("<{}", name);
+    let close_tag = format!("</{}", name);
+    let mut depth = 1;
+    let mut pos = content_start;
+
+    while depth > 0 {
+        let next_open = lower[pos..].find(&open_tag).map(|p| p + pos);
+        let next_close = lower[pos..].find(&close_tag).map(|p| p + pos)?;
+        match next_open {
+            Some(open) if open < next_close => {
+                depth += 1;
+                pos = open + open_tag.len();
+            }
+            _ => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&html[content_start..next_close]);
+                }
+                pos = next_close + close_tag.len();
+            }
+        }
+    }
+
+    None
+}
@@ -0,0 +1,130 @@
+use crate::salary::parse_salary;
+use crate::location::parse_location;
+use crate::schemas::NewJob;
+use crate::server::record_status;
+use crate::companies::{apply_blacklist, link_company};
+use crate::rules::apply_rules;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use log::info;
+
+pub fn process_new_jobs(conn: &Connection, source: Option<&str>) -> rusqlite::Result<Vec<(i32, String)>> {
+    // Search engines insert rows straight into the jobs table, so normalisation
+    // happens afterwards on anything not yet marked as processed. Sources running
+    // side by side each only pick up their own rows.
+    drop_purged(conn, source)?;
+
+    let pending = conn.prepare(
+        "SELECT id, title, location, salary, source, company FROM jobs WHERE processed = 0 AND (?1 IS NULL OR source = ?1)"
+    )?
+        .query_map([source], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+                row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+                row.get::<_, Option<String>>(5)?.unwrap_or_default(),
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let tx = conn.unchecked_transaction()?;
+    for (id, title, location, salary, _, company) in &pending {
+        let salary = salary.as_deref().and_then(parse_salary).unwrap_or_default();
+        let place = parse_location(location, title);
+        let company_id = link_company(&tx, company)?;
+
+        tx.execute(
+            "UPDATE jobs SET salary_min = ?1, salary_max = ?2, salary_currency = ?3, salary_period = ?4,
+                salary_annual = ?5, city = ?6, region = ?7, country = ?8, work_mode = ?9, company_id = ?10, processed = 1
+             WHERE id = ?11",
+            params![
+                salary.min, salary.max, salary.currency, salary.period, salary.annual,
+                place.city, place.region, place.country, place.work_mode, company_id, id
+            ],
+        )?;
+    }
+    if !pending.is_empty() {
+        let ids: Vec<i32> = pending.iter().map(|(id, ..)| *id).collect();
+        apply_rules(&tx, &ids)?;
+        apply_blacklist(&tx)?;
+    }
+    tx.commit()?;
+
+    if !pending.is_empty() {
+        info!("Processed {} new jobs", pending.len());
+    }
+
+    Ok(pending.into_iter().map(|(id, _, _, _, source, _)| (id, source)).collect())
+}
+
+fn drop_purged(conn: &Connection, source: Option<&str>) -> rusqlite::Result<()> {
+    // The engines don't know which jobs were purged, so anything they bring back is taken out again
+    let tx = conn.unchecked_transaction()?;
+    tx.execute(
+        "CREATE TEMP TABLE returned AS SELECT id FROM jobs
+         WHERE processed = 0 AND (?1 IS NULL OR source = ?1) AND uniqueid IN (SELECT uniqueid FROM purged_uniqueids)",
+        [source],
+    )?;
+    tx.execute("DELETE FROM job_history WHERE job_id IN (SELECT id FROM returned)", [])?;
+    let dropped = tx.execute("DELETE FROM jobs WHERE id IN (SELECT id FROM returned)", [])?;
+    tx.execute("UPDATE stats SET uniquejobs = MAX(uniquejobs - ?1, 0) WHERE id = 1", [dropped])?;
+    tx.execute("DROP TABLE returned", [])?;
+    tx.commit()?;
+
+    if dropped > 0 {
+        info!("Dropped {} purged jobs found again", dropped);
+    }
+    Ok(())
+}
+
+pub fn matches_query(job: &NewJob, keywords: &str, location: &str) -> bool {
+    // Feeds and ATS boards return everything they have, so the search terms are applied here instead
+    let text = format!("{} {} {}", job.title, job.company, job.description).to_lowercase();
+    let keywords_match = keywords.to_lowercase()
+        .split_whitespace()
+        .all(|keyword| text.contains(keyword));
+
+    // Listings that don't say where they are stay in, as do remote ones
+    let job_location = job.location.to_lowercase();
+    let location = location.trim().to_lowercase();
+    let location_match = location.is_empty()
+        || job_location.trim().is_empty()
+        || job_location.contains(&location)
+        || job_location.contains("remote");
+
+    keywords_match && location_match
+}
+
+pub fn insert_job(conn: &Connection, job: &NewJob) -> rusqlite::Result<Option<i32>> {
+    // Native sources insert the same way the search engines do, leaving processing to process_new_jobs
+    // Purged jobs count as already seen
+    let existing: Option<i32> = conn.query_row(
+        "SELECT id FROM jobs WHERE uniqueid = ?1 UNION ALL SELECT 0 FROM purged_uniqueids WHERE uniqueid = ?1",
+        [&job.uniqueid],
+        |row| row.get(0),
+    ).optional()?;
+    if existing.is_some() {
+        return Ok(None);
+    }
+
+    conn.execute(
+        "INSERT INTO jobs (uniqueid, title, company, location, salary, jobkey, fetched_date, read, appliedto, source,
+             description, apply_url, processed)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, datetime('now'), ?7, ?8, ?9, ?10, ?11, 0)",
+        params![
+            job.uniqueid, job.title, job.company, job.location, job.salary, job.jobkey,
+            job.read || job.appliedto, job.appliedto, job.source, job.description, job.apply_url
+        ],
+    )?;
+    let id = conn.last_insert_rowid() as i32;
+    conn.execute("UPDATE stats SET uniquejobs = uniquejobs + 1 WHERE id = 1", [])?;
+    record_status(conn, id, "added")?;
+    if job.appliedto {
+        conn.execute("UPDATE stats SET appliedjobs = appliedjobs + 1 WHERE id = 1", [])?;
+        record_status(conn, id, "applied")?;
+    }
+
+    Ok(Some(id))
+}
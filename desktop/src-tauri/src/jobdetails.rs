@@ -0,0 +1,157 @@
+use crate::schemas::{Job, JobDetails};
+use crate::server::{job_from_row, JOB_COLUMNS};
+use crate::html::{html_to_text, find_job_posting, element_inner_html, tag_inner_html};
+use crate::appconfig::write_job_description;
+use crate::crawler::fetch;
+use crate::helpers::get_db_path;
+
+use rusqlite::{params, Connection};
+use serde_json::json;
+use log::info;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn fetch_job_details(app_handle: AppHandle, job_id: i32) -> Result<Job, String> {
+    let db_path = get_db_path(&app_handle);
+    let job = {
+        let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+        conn.query_row(
+            &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
+            [job_id],
+            job_from_row,
+        ).map_err(|e| e.to_string())?
+    };
+
+    let url = listing_url(&job.source, &job.jobkey)
+        .ok_or_else(|| format!("Don't know where to find details for {} jobs", job.source))?;
+    info!("Fetching job details for {} from {}", job_id, url);
+
+    let page = fetch(&app_handle, &job.source, &url).await?;
+    let details = parse_job_page(&job.source, &page.body, &page.url)?;
+
+    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE jobs SET description = ?1, requirements = ?2, apply_url = ?3 WHERE id = ?4",
+        params![details.description, details.requirements, details.apply_url, job_id],
+    ).map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
+        [job_id],
+        job_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn use_job_description(app_handle: AppHandle, job_id: i32) -> Result<String, String> {
+    // Turns a stored job into the saved jobDescription.json used for cover letters
+    let db_path = get_db_path(&app_handle);
+    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    let job = conn.query_row(
+        &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
+        [job_id],
+        job_from_row,
+    ).map_err(|e| e.to_string())?;
+
+    if job.description.is_empty() {
+        return Err("Fetch the job details before using this job".to_string());
+    }
+
+    let key_requirements = match &job.extracted_requirements {
+        Some(requirements) => requirements.to_key_requirements(),
+        None => job.requirements.clone(),
+    };
+
+    let content = json!({
+        "jobTitle": job.title,
+        "company": job.company,
+        "jobDescription": job.description,
+        "keyRequirements": key_requirements,
+    }).to_string();
+
+    write_job_description(app_handle, content.clone())?;
+    Ok(content)
+}
+
+pub fn listing_url(source: &str, jobkey: &str) -> Option<String> {
+    if jobkey.starts_with("http://") || jobkey.starts_with("https://") {
+        return Some(jobkey.to_string());
+    }
+
+    match source {
+        "indeed" => Some(format!("https://www.indeed.com/viewjob?jk={}", jobkey)),
+        "jooble" => Some(format!("https://jooble.org/desc/{}", jobkey)),
+        _ => None,
+    }
+}
+
+pub fn parse_job_page(source: &str, page: &str, page_url: &str) -> Result<JobDetails, String> {
+    if let Some(posting) = find_job_posting(page) {
+        let description = html_to_text(posting["description"].as_str().unwrap_or_default());
+        if !description.is_empty() {
+            let apply_url = posting["url"].as_str()
+                .filter(|url| !url.is_empty())
+                .unwrap_or(page_url)
+                .to_string();
+            return Ok(JobDetails {
+                requirements: requirements_from(&description),
+                description,
+                apply_url,
+            });
+        }
+    }
+
+    let container = match source {
+        "indeed" => element_inner_html(page, "jobDescriptionText"),
+        _ => None,
+    };
+    // Without a known container the whole page would drag in navigation and footers, so give up instead
+    let description = container
+        .or_else(|| tag_inner_html(page, "main"))
+        .map(html_to_text)
+        .filter(|description| !description.is_empty())
+        .ok_or_else(|| format!("No job description found at {}", page_url))?;
+
+    Ok(JobDetails {
+        requirements: requirements_from(&description),
+        description,
+        apply_url: page_url.to_string(),
+    })
+}
+
+fn requirements_from(description: &str) -> String {
+    // Bullet points are where boards put requirements, the prose around them rarely is
+    description.lines()
+        .filter_map(|line| line.strip_prefix("- "))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn main_element_is_used_as_the_description() {
+        let page = "<html><nav>Home | Jobs</nav><main class=\"content\"><p>Build things</p><ul><li>Rust</li></ul></main>\
+                    <footer>Copyright</footer></html>";
+        let details = parse_job_page("url", page, "https://example.com/job").unwrap();
+
+        assert_eq!(details.description, "Build things\n- Rust");
+        assert_eq!(details.requirements, "Rust");
+        assert_eq!(details.apply_url, "https://example.com/job");
+    }
+
+    #[test]
+    fn pages_without_a_description_are_an_error() {
+        let page = "<html><nav>Home</nav><div id=\"main\"><p>Not a job</p></div><footer>Copyright</footer></html>";
+        assert!(parse_job_page("url", page, "https://example.com/job").is_err());
+    }
+
+    #[test]
+    fn indeed_container_wins_over_main() {
+        let page = "<main><div id=\"jobDescriptionText\"><p>The job</p></div><p>Similar jobs</p></main>";
+        let details = parse_job_page("indeed", page, "https://www.indeed.com/viewjob?jk=1").unwrap();
+        assert_eq!(details.description, "The job");
+    }
+}
@@ -0,0 +1,277 @@
+use crate::schemas::{Job, NewJob};
+use crate::server::{job_from_row, JOB_COLUMNS};
+use crate::html::{html_to_text, find_job_posting, meta_content, page_title};
+use crate::jobdetails::parse_job_page;
+use crate::crawler::fetch;
+use crate::ingest::{insert_job, process_new_jobs};
+use crate::helpers::get_db_path;
+
+use reqwest::Url;
+use rusqlite::Connection;
+use serde_json::Value;
+use std::time::{SystemTime, UNIX_EPOCH};
+use log::info;
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn add_job(app_handle: AppHandle, job: NewJob) -> Result<Job, String> {
+    if job.title.trim().is_empty() || job.company.trim().is_empty() {
+        return Err("Please enter at least a job title and company".to_string());
+    }
+
+    let mut job = NewJob {
+        title: job.title.trim().to_string(),
+        company: job.company.trim().to_string(),
+        location: job.location.trim().to_string(),
+        salary: job.salary.trim().to_string(),
+        source: "manual".to_string(),
+        ..job
+    };
+    if job.jobkey.is_empty() {
+        job.jobkey = job.apply_url.clone();
+    }
+    if job.uniqueid.is_empty() {
+        // Manual entries have no board id, so the posting link is the best stable key
+        job.uniqueid = if job.apply_url.is_empty() {
+            format!("manual:{}", timestamp())
+        } else {
+            format!("manual:{}", job.apply_url)
+        };
+    }
+
+    save_job(&app_handle, &job)
+}
+
+#[tauri::command]
+pub async fn import_job_from_url(app_handle: AppHandle, url: String) -> Result<Job, String> {
+    // Accepts whatever was pasted, as long as there is a link somewhere in it
+    let url = url.split_whitespace()
+        .find(|word| word.starts_with("http://") || word.starts_with("https://"))
+        .ok_or_else(|| "No link found in the pasted text".to_string())?;
+    Url::parse(url).map_err(|e| format!("Invalid URL: {}", e))?;
+
+    info!("Importing job from {}", url);
+    let page = fetch(&app_handle, "url", url).await?;
+    let job = parse_posting_page(&page.body, &page.url)
+        .ok_or_else(|| format!("Couldn't find a job posting at {}", url))?;
+
+    save_job(&app_handle, &job)
+}
+
+fn save_job(app_handle: &AppHandle, job: &NewJob) -> Result<Job, String> {
+    let conn = Connection::open(get_db_path(app_handle)).map_err(|e| e.to_string())?;
+    let job_id = insert_job(&conn, job)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "This job is already saved".to_string())?;
+    process_new_jobs(&conn, Some(&job.source)).map_err(|e| e.to_string())?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
+        [job_id],
+        job_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+pub fn parse_posting_page(page: &str, page_url: &str) -> Option<NewJob> {
+    if let Some(posting) = find_job_posting(page) {
+        return Some(posting_to_job(&posting, page_url));
+    }
+
+    // Without structured data, fall back to Open Graph tags and the page text
+    let title = meta_content(page, "og:title").or_else(|| page_title(page))?;
+    let (description, apply_url) = match parse_job_page("url", page, page_url) {
+        Ok(details) => (details.description, details.apply_url),
+        Err(_) => (String::new(), page_url.to_string()),
+    };
+    let company = meta_content(page, "og:site_name")
+        .or_else(|| Url::parse(page_url).ok().and_then(|url| url.host_str().map(str::to_string)))
+        .unwrap_or_default();
+
+    Some(NewJob {
+        uniqueid: format!("url:{}", page_url),
+        title,
+        company,
+        jobkey: page_url.to_string(),
+        source: "url".to_string(),
+        description,
+        apply_url,
+        ..Default::default()
+    })
+}
+
+fn posting_to_job(posting: &Value, page_url: &str) -> NewJob {
+    let apply_url = text(&posting["url"]).unwrap_or_else(|| page_url.to_string());
+    // Identifiers are only unique per employer, so they are namespaced by the site they came from
+    let identifier = text(&posting["identifier"]["value"]).or_else(|| text(&posting["identifier"]));
+    let host = Url::parse(page_url).ok().and_then(|url| url.host_str().map(str::to_string));
+    let uniqueid = match (host, identifier) {
+        (Some(host), Some(identifier)) => format!("url:{}:{}", host, identifier),
+        _ => format!("url:{}", apply_url),
+    };
+
+    NewJob {
+        uniqueid,
+        title: text(&posting["title"]).unwrap_or_default(),
+        company: text(&posting["hiringOrganization"]["name"])
+            .or_else(|| text(&posting["hiringOrganization"]))
+            .unwrap_or_default(),
+        location: posting_location(posting),
+        salary: posting_salary(&posting["baseSalary"]),
+        jobkey: page_url.to_string(),
+        source: "url".to_string(),
+        description: html_to_text(&text(&posting["description"]).unwrap_or_default()),
+        apply_url,
+        ..Default::default()
+    }
+}
+
+fn posting_location(posting: &Value) -> String {
+    // jobLocation can be a single Place or a list of them, only the first is kept
+    let place = match &posting["jobLocation"] {
+        Value::Array(places) => places.first().cloned().unwrap_or_default(),
+        place => place.clone(),
+    };
+    let address = &place["address"];
+
+    let mut parts: Vec<String> = ["addressLocality", "addressRegion"].iter()
+        .filter_map(|field| text(&address[*field]))
+        .collect();
+    if let Some(country) = text(&address["addressCountry"]["name"]).or_else(|| text(&address["addressCountry"])) {
+        parts.push(country);
+    }
+
+    let location = parts.join(", ");
+    if text(&posting["jobLocationType"]).map(|t| t == "TELECOMMUTE").unwrap_or(false) {
+        if location.is_empty() { "Remote".to_string() } else { format!("Remote in {}", location) }
+    } else {
+        location
+    }
+}
+
+fn posting_salary(salary: &Value) -> String {
+    let value = &salary["value"];
+    let (min, max) = match (value["minValue"].as_f64(), value["maxValue"].as_f64(), value["value"].as_f64()) {
+        (Some(min), Some(max), _) => (min, max),
+        (Some(amount), None, _) | (None, Some(amount), _) | (None, None, Some(amount)) => (amount, amount),
+        _ => return text(salary).unwrap_or_default(),
+    };
+
+    // Written out so parse_salary can read it like any other listing
+    let currency = text(&salary["currency"]).unwrap_or_default();
+    let period = text(&value["unitText"]).unwrap_or_else(|| "year".to_string()).to_lowercase();
+    if min == max {
+        format!("{} {} per {}", currency, min, period).trim().to_string()
+    } else {
+        format!("{} {} - {} per {}", currency, min, max, period).trim().to_string()
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    value.as_str().map(|s| s.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn timestamp() -> u128 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(json_ld: &str) -> String {
+        format!(
+            "<html><head><title>Jobs</title><script type=\"application/ld+json\">{}</script></head><body></body></html>",
+            json_ld
+        )
+    }
+
+    #[test]
+    fn reads_a_json_ld_job_posting() {
+        let html = page(r#"{
+            "@context": "https://schema.org",
+            "@type": "JobPosting",
+            "title": "Backend Developer",
+            "identifier": {"@type": "PropertyValue", "name": "Acme", "value": "12345"},
+            "hiringOrganization": {"@type": "Organization", "name": "Acme"},
+            "description": "<p>Build <b>APIs</b> in Rust.</p>",
+            "url": "https://acme.example.com/jobs/12345/apply",
+            "jobLocation": {"@type": "Place", "address": {"addressLocality": "Milano", "addressRegion": "Lombardia",
+                "addressCountry": {"@type": "Country", "name": "IT"}}}
+        }"#);
+        let job = parse_posting_page(&html, "https://acme.example.com/jobs/12345").unwrap();
+
+        assert_eq!(job.uniqueid, "url:acme.example.com:12345");
+        assert_eq!(job.title, "Backend Developer");
+        assert_eq!(job.company, "Acme");
+        assert_eq!(job.location, "Milano, Lombardia, IT");
+        assert_eq!(job.description, "Build APIs in Rust.");
+        assert_eq!(job.apply_url, "https://acme.example.com/jobs/12345/apply");
+        assert_eq!(job.jobkey, "https://acme.example.com/jobs/12345");
+        assert_eq!(job.source, "url");
+    }
+
+    #[test]
+    fn the_same_identifier_on_two_sites_is_two_jobs() {
+        let html = page(r#"{"@type": "JobPosting", "title": "Developer", "identifier": "12345"}"#);
+        let first = parse_posting_page(&html, "https://one.example.com/job").unwrap();
+        let second = parse_posting_page(&html, "https://two.example.com/job").unwrap();
+        assert_ne!(first.uniqueid, second.uniqueid);
+    }
+
+    #[test]
+    fn finds_postings_in_a_graph_or_array() {
+        let graph = page(r#"{"@context": "https://schema.org", "@graph": [
+            {"@type": "WebPage", "name": "Careers"},
+            {"@type": "JobPosting", "title": "Data Engineer", "hiringOrganization": "Snake Analytics"}
+        ]}"#);
+        let job = parse_posting_page(&graph, "https://snake.example.com/careers/1").unwrap();
+        assert_eq!(job.title, "Data Engineer");
+        assert_eq!(job.company, "Snake Analytics");
+        assert_eq!(job.uniqueid, "url:https://snake.example.com/careers/1");
+
+        let array = page(r#"[{"@type": "Organization"}, {"@type": ["JobPosting"], "title": "Designer"}]"#);
+        assert_eq!(parse_posting_page(&array, "https://example.com/jobs/2").unwrap().title, "Designer");
+    }
+
+    #[test]
+    fn falls_back_to_open_graph_and_page_text() {
+        let html = r#"<html><head>
+            <meta property="og:title" content="Frontend Developer">
+            <meta property="og:site_name" content="Svelte Studio">
+            </head><body><main><h1>Frontend Developer</h1><p>Svelte and TypeScript.</p></main></body></html>"#;
+        let job = parse_posting_page(html, "https://svelte.example.com/jobs/7").unwrap();
+
+        assert_eq!(job.title, "Frontend Developer");
+        assert_eq!(job.company, "Svelte Studio");
+        assert_eq!(job.uniqueid, "url:https://svelte.example.com/jobs/7");
+        assert!(job.description.contains("Svelte and TypeScript."));
+    }
+
+    #[test]
+    fn pages_without_a_title_are_not_postings() {
+        assert!(parse_posting_page("<html><body><p>Hello</p></body></html>", "https://example.com").is_none());
+    }
+
+    #[test]
+    fn salaries_are_written_out_for_parse_salary() {
+        let range = serde_json::json!({"currency": "EUR", "value": {"minValue": 40000, "maxValue": 50000, "unitText": "YEAR"}});
+        assert_eq!(posting_salary(&range), "EUR 40000 - 50000 per year");
+
+        let single = serde_json::json!({"currency": "GBP", "value": {"value": 25, "unitText": "HOUR"}});
+        assert_eq!(posting_salary(&single), "GBP 25 per hour");
+
+        assert_eq!(posting_salary(&serde_json::json!("£30k")), "£30k");
+        assert_eq!(posting_salary(&Value::Null), "");
+    }
+
+    #[test]
+    fn remote_postings_say_so_in_the_location() {
+        let remote = serde_json::json!({
+            "jobLocationType": "TELECOMMUTE",
+            "jobLocation": [{"address": {"addressCountry": "GB"}}, {"address": {"addressCountry": "IE"}}]
+        });
+        assert_eq!(posting_location(&remote), "Remote in GB");
+        assert_eq!(posting_location(&serde_json::json!({"jobLocationType": "TELECOMMUTE"})), "Remote");
+        assert_eq!(posting_location(&serde_json::json!({})), "");
+    }
+}
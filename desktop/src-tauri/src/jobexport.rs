@@ -0,0 +1,310 @@
+use crate::schemas::{Job, JobExport, JobFilter, JobImportSummary, JobStatusChange};
+use crate::server::{filter_clause, job_from_row, record_status, JOB_COLUMNS};
+use crate::ingest::process_new_jobs;
+use crate::jobnotes::add_tags;
+use crate::helpers::get_db_path;
+
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::Path;
+use log::info;
+use tauri::AppHandle;
+
+// CSV column order, every Job field followed by the status history
+const EXPORT_COLUMNS: &[&str] = &[
+    "id", "uniqueid", "title", "company", "location", "salary", "jobkey", "fetched_date", "read", "appliedto",
+    "source", "match_score", "matched_skills", "missing_skills", "description", "requirements", "apply_url",
+    "extracted_requirements", "salary_min", "salary_max", "salary_currency", "salary_period", "salary_annual",
+    "city", "region", "country", "work_mode", "tags", "company_id", "hidden", "archived_at", "status_history",
+];
+
+// Everything else is written as JSON so lists and numbers survive a round trip
+const TEXT_COLUMNS: &[&str] = &[
+    "uniqueid", "title", "company", "location", "salary", "jobkey", "fetched_date", "source", "description",
+    "requirements", "apply_url",
+];
+
+// Plain text too, but an empty cell means the value was never set
+const OPTIONAL_TEXT_COLUMNS: &[&str] = &[
+    "salary_currency", "salary_period", "city", "region", "country", "work_mode", "archived_at",
+];
+
+#[tauri::command]
+pub fn export_jobs(app_handle: AppHandle, format: String, filter: Option<JobFilter>, path: String) -> Result<usize, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let (filter_sql, filter_values) = filter_clause(&filter.unwrap_or_default());
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM jobs WHERE 1 = 1{} ORDER BY fetched_date",
+        JOB_COLUMNS, filter_sql
+    )).map_err(|e| e.to_string())?;
+    let jobs = stmt.query_map(params_from_iter(filter_values), job_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let exports = jobs.into_iter()
+        .map(|job| Ok(JobExport { status_history: status_history(&conn, job.id)?, job }))
+        .collect::<rusqlite::Result<Vec<_>>>()
+        .map_err(|e| e.to_string())?;
+
+    let content = match format.to_lowercase().as_str() {
+        "json" => serde_json::to_string_pretty(&exports).map_err(|e| e.to_string())?,
+        "csv" => to_csv(&exports)?,
+        other => return Err(format!("Unsupported export format: {}", other)),
+    };
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+
+    info!("Exported {} jobs to {}", exports.len(), path);
+    Ok(exports.len())
+}
+
+#[tauri::command]
+pub fn import_jobs(app_handle: AppHandle, path: String) -> Result<JobImportSummary, String> {
+    let content = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let is_json = Path::new(&path).extension().map(|ext| ext.eq_ignore_ascii_case("json")).unwrap_or(false)
+        || content.trim_start().starts_with('[');
+    let exports = if is_json {
+        serde_json::from_str::<Vec<JobExport>>(&content).map_err(|e| format!("Not a job export: {}", e))?
+    } else {
+        from_csv(&content)?
+    };
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    let mut summary = JobImportSummary::default();
+
+    for export in &exports {
+        if export.job.uniqueid.is_empty() {
+            continue;
+        }
+        let (job_id, added, newly_applied) = merge_job(&tx, &export.job).map_err(|e| e.to_string())?;
+        if added {
+            summary.added += 1;
+        } else {
+            summary.merged += 1;
+        }
+        merge_history(&tx, job_id, &export.status_history).map_err(|e| e.to_string())?;
+        // Spreadsheets may have lost the history, the application still gets recorded
+        if newly_applied && !export.status_history.iter().any(|change| change.status == "applied") {
+            record_status(&tx, job_id, "applied").map_err(|e| e.to_string())?;
+        }
+        add_tags(&tx, job_id, &export.job.tags).map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    process_new_jobs(&conn, None).map_err(|e| e.to_string())?;
+
+    info!("Imported {} jobs from {} ({} new)", exports.len(), path, summary.added);
+    Ok(summary)
+}
+
+fn merge_job(conn: &Connection, job: &Job) -> rusqlite::Result<(i32, bool, bool)> {
+    // Jobs are matched on uniqueid, so the same listing found by two people merges into one row.
+    // Returns the job id, whether it was added and whether it only now counts as applied to.
+    let existing: Option<(i32, bool)> = conn.query_row(
+        "SELECT id, appliedto FROM jobs WHERE uniqueid = ?1",
+        [&job.uniqueid],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional()?;
+
+    let extracted = job.extracted_requirements.as_ref()
+        .and_then(|requirements| serde_json::to_string(requirements).ok());
+    let matched = serde_json::to_string(&job.matched_skills).unwrap_or_default();
+    let missing = serde_json::to_string(&job.missing_skills).unwrap_or_default();
+
+    if let Some((job_id, was_applied)) = existing {
+        // Statuses only ever move forward, and local details win over imported ones
+        conn.execute(
+            "UPDATE jobs SET
+                read = MAX(read, ?1),
+                appliedto = MAX(appliedto, ?2),
+                description = COALESCE(NULLIF(description, ''), ?3),
+                requirements = COALESCE(NULLIF(requirements, ''), ?4),
+                apply_url = COALESCE(NULLIF(apply_url, ''), ?5),
+                extracted_requirements = COALESCE(extracted_requirements, ?6),
+                match_score = COALESCE(match_score, ?7),
+                matched_skills = COALESCE(matched_skills, ?8),
+                missing_skills = COALESCE(missing_skills, ?9)
+             WHERE id = ?10",
+            params![
+                job.read, job.appliedto, job.description, job.requirements, job.apply_url, extracted,
+                job.match_score, matched, missing, job_id
+            ],
+        )?;
+
+        let newly_applied = job.appliedto && !was_applied;
+        if newly_applied {
+            conn.execute("UPDATE stats SET appliedjobs = appliedjobs + 1 WHERE id = 1", [])?;
+        }
+        return Ok((job_id, false, newly_applied));
+    }
+
+    conn.execute(
+        "INSERT INTO jobs (uniqueid, title, company, location, salary, jobkey, fetched_date, read, appliedto, source,
+             description, requirements, apply_url, extracted_requirements, match_score, matched_skills,
+             missing_skills, hidden, archived_at, processed)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, 0)",
+        params![
+            job.uniqueid, job.title, job.company, job.location, job.salary, job.jobkey, job.fetched_date,
+            job.read, job.appliedto, job.source, job.description, job.requirements, job.apply_url, extracted,
+            job.match_score, matched, missing, job.hidden, job.archived_at
+        ],
+    )?;
+    let job_id = conn.last_insert_rowid() as i32;
+
+    conn.execute("UPDATE stats SET uniquejobs = uniquejobs + 1 WHERE id = 1", [])?;
+    if job.appliedto {
+        conn.execute("UPDATE stats SET appliedjobs = appliedjobs + 1 WHERE id = 1", [])?;
+    }
+
+    // company_id isn't carried over, it points into the exporting database and gets relinked on processing
+    Ok((job_id, true, job.appliedto))
+}
+
+fn merge_history(conn: &Connection, job_id: i32, history: &[JobStatusChange]) -> rusqlite::Result<()> {
+    for change in history {
+        conn.execute(
+            "INSERT INTO job_history (job_id, status, changed_at)
+             SELECT ?1, ?2, ?3
+             WHERE NOT EXISTS (SELECT 1 FROM job_history WHERE job_id = ?1 AND status = ?2 AND changed_at = ?3)",
+            params![job_id, change.status, change.changed_at],
+        )?;
+    }
+    Ok(())
+}
+
+fn status_history(conn: &Connection, job_id: i32) -> rusqlite::Result<Vec<JobStatusChange>> {
+    let mut stmt = conn.prepare("SELECT status, changed_at FROM job_history WHERE job_id = ?1 ORDER BY changed_at, id")?;
+    let history = stmt.query_map([job_id], |row| {
+        Ok(JobStatusChange {
+            status: row.get(0)?,
+            changed_at: row.get(1)?,
+        })
+    })?.collect();
+    history
+}
+
+fn to_csv(exports: &[JobExport]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(EXPORT_COLUMNS).map_err(|e| e.to_string())?;
+
+    for export in exports {
+        let value = serde_json::to_value(export).map_err(|e| e.to_string())?;
+        let record: Vec<String> = EXPORT_COLUMNS.iter()
+            .map(|column| match &value[*column] {
+                Value::Null => String::new(),
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+        writer.write_record(&record).map_err(|e| e.to_string())?;
+    }
+
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+fn from_csv(content: &str) -> Result<Vec<JobExport>, String> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers().map_err(|e| e.to_string())?.clone();
+
+    let mut exports = Vec::new();
+    for (line, record) in reader.records().enumerate() {
+        let record = record.map_err(|e| e.to_string())?;
+        let mut object = Map::new();
+
+        for (column, cell) in headers.iter().zip(record.iter()) {
+            let is_text = TEXT_COLUMNS.contains(&column) || (OPTIONAL_TEXT_COLUMNS.contains(&column) && !cell.is_empty());
+            let value = if is_text {
+                Value::String(cell.to_string())
+            } else if cell.is_empty() {
+                Value::Null
+            } else {
+                serde_json::from_str(cell).map_err(|e| format!("Row {}, column {}: {}", line + 2, column, e))?
+            };
+            object.insert(column.to_string(), value);
+        }
+
+        // Spreadsheets tend to drop empty list columns, fill them in rather than failing
+        for column in ["matched_skills", "missing_skills", "tags", "status_history"] {
+            if object.get(column).map(Value::is_null).unwrap_or(true) {
+                object.insert(column.to_string(), Value::Array(Vec::new()));
+            }
+        }
+
+        let export = serde_json::from_value(Value::Object(object))
+            .map_err(|e| format!("Row {}: {}", line + 2, e))?;
+        exports.push(export);
+    }
+
+    Ok(exports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+    use crate::schemas::NewJob;
+    use crate::ingest::insert_job;
+
+    fn exported(conn: &Connection, job_id: i32) -> JobExport {
+        let job = conn.query_row(&format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS), [job_id], job_from_row)
+            .unwrap();
+        JobExport { status_history: status_history(conn, job_id).unwrap(), job }
+    }
+
+    fn applied_count(conn: &Connection) -> i32 {
+        conn.query_row("SELECT appliedjobs FROM stats WHERE id = 1", [], |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn csv_round_trip_keeps_every_column() {
+        let conn = test_connection();
+        let job_id = insert_job(&conn, &NewJob {
+            uniqueid: "csv-1".to_string(),
+            title: "Rust Developer".to_string(),
+            company: "Ferris Labs".to_string(),
+            location: "Milano (MI)".to_string(),
+            salary: "€50,000 a year".to_string(),
+            source: "manual".to_string(),
+            ..Default::default()
+        }).unwrap().unwrap();
+        process_new_jobs(&conn, None).unwrap();
+        conn.execute("UPDATE jobs SET hidden = 1, archived_at = '2024-05-01 10:00:00' WHERE id = ?1", [job_id]).unwrap();
+
+        let export = exported(&conn, job_id);
+        let imported = from_csv(&to_csv(std::slice::from_ref(&export)).unwrap()).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&imported[0]).unwrap(),
+            serde_json::to_value(&export).unwrap()
+        );
+        assert!(imported[0].job.hidden);
+        assert_eq!(imported[0].job.archived_at.as_deref(), Some("2024-05-01 10:00:00"));
+        assert!(imported[0].job.company_id.is_some());
+        assert_eq!(imported[0].job.work_mode, None);
+    }
+
+    #[test]
+    fn merging_an_application_counts_it_once() {
+        let conn = test_connection();
+        let job_id = insert_job(&conn, &NewJob {
+            uniqueid: "merge-1".to_string(),
+            title: "Backend Engineer".to_string(),
+            ..Default::default()
+        }).unwrap().unwrap();
+
+        let mut export = exported(&conn, job_id);
+        export.job.appliedto = true;
+        export.status_history.clear();
+
+        assert_eq!(merge_job(&conn, &export.job).unwrap(), (job_id, false, true));
+        assert_eq!(applied_count(&conn), 1);
+
+        // Importing the same file again changes nothing
+        assert_eq!(merge_job(&conn, &export.job).unwrap(), (job_id, false, false));
+        assert_eq!(applied_count(&conn), 1);
+    }
+}
@@ -0,0 +1,298 @@
+use crate::schemas::{JobContact, JobNote, Tag};
+use crate::helpers::get_db_path;
+
+use rusqlite::{params, Connection, ErrorCode};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub fn list_job_notes(app_handle: AppHandle, job_id: i32) -> Result<Vec<JobNote>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT id, job_id, body, created_at, updated_at FROM job_notes WHERE job_id = ?1 ORDER BY created_at, id"
+    ).map_err(|e| e.to_string())?;
+
+    let notes = stmt.query_map([job_id], note_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(notes)
+}
+
+#[tauri::command]
+pub fn add_job_note(app_handle: AppHandle, job_id: i32, body: String) -> Result<JobNote, String> {
+    if body.trim().is_empty() {
+        return Err("The note is empty".to_string());
+    }
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO job_notes (job_id, body) VALUES (?1, ?2)",
+        params![job_id, body.trim()],
+    ).map_err(|e| e.to_string())?;
+
+    get_note(&conn, conn.last_insert_rowid() as i32)
+}
+
+#[tauri::command]
+pub fn update_job_note(app_handle: AppHandle, note_id: i32, body: String) -> Result<JobNote, String> {
+    if body.trim().is_empty() {
+        return Err("The note is empty".to_string());
+    }
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE job_notes SET body = ?1, updated_at = datetime('now') WHERE id = ?2",
+        params![body.trim(), note_id],
+    ).map_err(|e| e.to_string())?;
+
+    get_note(&conn, note_id)
+}
+
+#[tauri::command]
+pub fn delete_job_note(app_handle: AppHandle, note_id: i32) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM job_notes WHERE id = ?1", [note_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_tags(app_handle: AppHandle) -> Result<Vec<Tag>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT tags.id, tags.name, COUNT(job_tags.job_id)
+         FROM tags
+         LEFT JOIN job_tags ON job_tags.tag_id = tags.id
+         GROUP BY tags.id
+         ORDER BY tags.name"
+    ).map_err(|e| e.to_string())?;
+
+    let tags = stmt.query_map([], |row| {
+        Ok(Tag {
+            id: row.get(0)?,
+            name: row.get(1)?,
+            job_count: row.get(2)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(tags)
+}
+
+#[tauri::command]
+pub fn set_job_tags(app_handle: AppHandle, job_id: i32, tags: Vec<String>) -> Result<Vec<String>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    replace_tags(&conn, job_id, &tags).map_err(|e| e.to_string())?;
+    job_tags(&conn, job_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn rename_tag(app_handle: AppHandle, tag_id: i32, name: String) -> Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Tags need a name".to_string());
+    }
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    rename(&conn, tag_id, name)
+}
+
+#[tauri::command]
+pub fn delete_tag(app_handle: AppHandle, tag_id: i32) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM job_tags WHERE tag_id = ?1", [tag_id])
+        .map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM tags WHERE id = ?1", [tag_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_job_contacts(app_handle: AppHandle, job_id: i32) -> Result<Vec<JobContact>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT id, job_id, name, email, role FROM job_contacts WHERE job_id = ?1 ORDER BY name"
+    ).map_err(|e| e.to_string())?;
+
+    let contacts = stmt.query_map([job_id], contact_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(contacts)
+}
+
+#[tauri::command]
+pub fn add_job_contact(app_handle: AppHandle, job_id: i32, contact: JobContact) -> Result<JobContact, String> {
+    validate_contact(&contact)?;
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO job_contacts (job_id, name, email, role) VALUES (?1, ?2, ?3, ?4)",
+        params![job_id, contact.name.trim(), contact.email.trim(), contact.role.trim()],
+    ).map_err(|e| e.to_string())?;
+
+    get_contact(&conn, conn.last_insert_rowid() as i32)
+}
+
+#[tauri::command]
+pub fn update_job_contact(app_handle: AppHandle, contact_id: i32, contact: JobContact) -> Result<JobContact, String> {
+    validate_contact(&contact)?;
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE job_contacts SET name = ?1, email = ?2, role = ?3 WHERE id = ?4",
+        params![contact.name.trim(), contact.email.trim(), contact.role.trim(), contact_id],
+    ).map_err(|e| e.to_string())?;
+
+    get_contact(&conn, contact_id)
+}
+
+#[tauri::command]
+pub fn delete_job_contact(app_handle: AppHandle, contact_id: i32) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM job_contacts WHERE id = ?1", [contact_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn add_tags(conn: &Connection, job_id: i32, tags: &[String]) -> rusqlite::Result<()> {
+    for tag in tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+        // Names are unique ignoring case, so "Remote" and "remote" end up as one tag
+        conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])?;
+        conn.execute(
+            "INSERT OR IGNORE INTO job_tags (job_id, tag_id) SELECT ?1, id FROM tags WHERE name = ?2",
+            params![job_id, tag],
+        )?;
+    }
+    Ok(())
+}
+
+fn replace_tags(conn: &Connection, job_id: i32, tags: &[String]) -> rusqlite::Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM job_tags WHERE job_id = ?1", [job_id])?;
+    add_tags(&tx, job_id, tags)?;
+    tx.commit()
+}
+
+fn rename(conn: &Connection, tag_id: i32, name: &str) -> Result<(), String> {
+    match conn.execute("UPDATE tags SET name = ?1 WHERE id = ?2", params![name, tag_id]) {
+        Ok(_) => Ok(()),
+        Err(rusqlite::Error::SqliteFailure(error, _)) if error.code == ErrorCode::ConstraintViolation => {
+            Err(format!("There is already a tag called {}", name))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn job_tags(conn: &Connection, job_id: i32) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT tags.name FROM job_tags JOIN tags ON tags.id = job_tags.tag_id WHERE job_tags.job_id = ?1 ORDER BY tags.name"
+    )?;
+    let tags = stmt.query_map([job_id], |row| row.get(0))?.collect();
+    tags
+}
+
+fn get_note(conn: &Connection, note_id: i32) -> Result<JobNote, String> {
+    conn.query_row(
+        "SELECT id, job_id, body, created_at, updated_at FROM job_notes WHERE id = ?1",
+        [note_id],
+        note_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+fn get_contact(conn: &Connection, contact_id: i32) -> Result<JobContact, String> {
+    conn.query_row(
+        "SELECT id, job_id, name, email, role FROM job_contacts WHERE id = ?1",
+        [contact_id],
+        contact_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+fn note_from_row(row: &rusqlite::Row) -> rusqlite::Result<JobNote> {
+    Ok(JobNote {
+        id: row.get(0)?,
+        job_id: row.get(1)?,
+        body: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+fn contact_from_row(row: &rusqlite::Row) -> rusqlite::Result<JobContact> {
+    Ok(JobContact {
+        id: row.get(0)?,
+        job_id: row.get(1)?,
+        name: row.get(2)?,
+        email: row.get(3)?,
+        role: row.get(4)?,
+    })
+}
+
+fn validate_contact(contact: &JobContact) -> Result<(), String> {
+    if contact.name.trim().is_empty() {
+        return Err("Contacts need a name".to_string());
+    }
+    let email = contact.email.trim();
+    if !email.is_empty() && !email.contains('@') {
+        return Err(format!("{} doesn't look like an email address", email));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+
+    fn tags(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn tags_differing_only_in_case_are_one_tag() {
+        let conn = test_connection();
+        add_tags(&conn, 1, &tags(&["Remote", " remote ", "REMOTE", "Rust"])).unwrap();
+        add_tags(&conn, 2, &tags(&["rust"])).unwrap();
+
+        // The first spelling is the one that sticks
+        assert_eq!(job_tags(&conn, 1).unwrap(), tags(&["Remote", "Rust"]));
+        assert_eq!(job_tags(&conn, 2).unwrap(), tags(&["Rust"]));
+        let count: i32 = conn.query_row("SELECT COUNT(*) FROM tags", [], |row| row.get(0)).unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn setting_tags_replaces_the_old_ones() {
+        let conn = test_connection();
+        add_tags(&conn, 1, &tags(&["Remote", "Rust"])).unwrap();
+        add_tags(&conn, 2, &tags(&["Remote"])).unwrap();
+
+        replace_tags(&conn, 1, &tags(&["Applied", "rust", ""])).unwrap();
+        assert_eq!(job_tags(&conn, 1).unwrap(), tags(&["Applied", "Rust"]));
+        // Other jobs keep their tags
+        assert_eq!(job_tags(&conn, 2).unwrap(), tags(&["Remote"]));
+
+        replace_tags(&conn, 1, &[]).unwrap();
+        assert!(job_tags(&conn, 1).unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_duplicate_names_are_reported_as_duplicates() {
+        let conn = test_connection();
+        add_tags(&conn, 1, &tags(&["Remote", "Rust"])).unwrap();
+        let rust_id: i32 = conn.query_row("SELECT id FROM tags WHERE name = 'Rust'", [], |row| row.get(0)).unwrap();
+
+        assert_eq!(rename(&conn, rust_id, "remote").unwrap_err(), "There is already a tag called remote");
+        rename(&conn, rust_id, "Rust (backend)").unwrap();
+        assert_eq!(job_tags(&conn, 1).unwrap(), tags(&["Remote", "Rust (backend)"]));
+
+        conn.execute("DROP TABLE job_tags", []).unwrap();
+        conn.execute("DROP TABLE tags", []).unwrap();
+        let error = rename(&conn, rust_id, "Go").unwrap_err();
+        assert!(error.contains("no such table"), "{}", error);
+    }
+}
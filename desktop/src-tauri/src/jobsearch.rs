@@ -1,87 +1,485 @@
+use crate::schemas::{CrawlPolicy, SearchReport, SearchProgress, SearchQuery, SearchAllReport};
 use crate::helpers::get_db_path;
-use std::process::Command;
+use crate::ingest::process_new_jobs;
+use crate::crawler::{begin_run, check_robots, http_client, load_policy, retry_backoff, Crawler};
+use crate::feeds::run_feeds;
+use crate::ats::run_ats;
+use crate::searches::Scheduler;
+use rusqlite::{params, Connection};
+use std::future::Future;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 use log::{info, error};
-use tauri::AppHandle;
-use tokio::task;
+use tauri::{AppHandle, Manager};
+use tokio::{task, time};
+use reqwest::Url;
+
+// Engines page through results slowly, but one that hangs shouldn't hold up the others.
+// Every source gets one deadline that covers its retries and robots checks too.
+const SOURCE_TIMEOUT: Duration = Duration::from_secs(180);
+
+pub struct SourceRun {
+    pub report: SearchReport,
+    pub new_job_ids: Vec<i32>,
+}
 
 #[tauri::command]
-pub async fn run_indeed_search(app_handle: AppHandle, keywords: String, location: String) -> Result<String, String> {
+pub async fn run_indeed_search(app_handle: AppHandle, keywords: String, location: String) -> Result<SearchReport, String> {
+    let scheduler = app_handle.state::<Scheduler>();
+    let _guard = scheduler.lock().await;
+    let run = run_source(&app_handle, "indeed", keywords, location, false, Instant::now() + SOURCE_TIMEOUT).await?;
+    info!("Indeed search completed: {:?}", run.report);
+    engine_result(run.report)
+}
 
-    let db_path = get_db_path(&app_handle);
-    let indeed_executable = app_handle
-        .path_resolver()
-        .resolve_resource("resources/indeedsearchengine")
-        .expect("Failed to resolve Indeed search engine executable path");
-    info!("Indeed executable path: {:?}", indeed_executable);
+#[tauri::command]
+pub async fn run_jooble_search(app_handle: AppHandle, keywords: String, location: String) -> Result<SearchReport, String> {
+    let scheduler = app_handle.state::<Scheduler>();
+    let _guard = scheduler.lock().await;
+    let run = run_source(&app_handle, "jooble", keywords, location, false, Instant::now() + SOURCE_TIMEOUT).await?;
+    info!("Jooble search completed: {:?}", run.report);
+    engine_result(run.report)
+}
 
-    let result = task::spawn_blocking(move || {
-        info!("Executing Indeed search engine...");
-        let output = Command::new(&indeed_executable)
-            .arg(db_path.to_str().unwrap())
-            .arg(&keywords)
-            .arg(&location)
-            .output()
-            .map_err(|e| {
-                let error_msg = format!("Failed to execute Indeed search engine: {}", e);
-                error!("{}", error_msg);
-                error_msg
-            })?;
-
-        if !output.status.success() {
-            let error_msg = format!("Indeed search engine execution failed. Exit code: {:?}\nStderr: {}",
-                output.status.code(),
-                String::from_utf8_lossy(&output.stderr));
-            error!("{}", error_msg);
-            return Err(error_msg);
-        }
+#[tauri::command]
+pub async fn search_all(app_handle: AppHandle, query: SearchQuery, sources: Vec<String>) -> Result<SearchAllReport, String> {
+    if query.keywords.trim().is_empty() {
+        return Err("Please enter job keywords".to_string());
+    }
+    if sources.is_empty() {
+        return Err("Pick at least one job source".to_string());
+    }
 
-        let success_msg = format!("Indeed search completed successfully. Stdout: {}",
-            String::from_utf8_lossy(&output.stdout));
-        info!("{}", success_msg);
-        Ok(success_msg)
-    }).await.map_err(|e| format!("Task join error: {}", e))?;
+    // Manual searches queue behind saved searches, backups and restores like they queue behind each other
+    let scheduler = app_handle.state::<Scheduler>();
+    let _guard = scheduler.lock().await;
+    let (report, _) = search_sources(&app_handle, &query, &sources, false).await;
+    info!("Search across {} sources inserted {} jobs", report.reports.len(), report.inserted);
+    let _ = app_handle.emit_all("search-all-finished", &report);
 
-    result
+    // Only fail outright when nothing worked, partial results are still worth showing
+    if report.reports.iter().all(|run| !run.errors.is_empty()) {
+        return Err(report.errors.join("\n"));
+    }
+    Ok(report)
+}
+
+fn engine_result(report: SearchReport) -> Result<SearchReport, String> {
+    // A run that failed without saving anything is an error, one that saved some jobs reports its errors alongside them
+    if !report.errors.is_empty() && report.inserted == 0 {
+        return Err(report.errors.join("\n"));
+    }
+    Ok(report)
+}
+
+pub async fn search_sources(app_handle: &AppHandle, query: &SearchQuery, sources: &[String], scheduled: bool) -> (SearchAllReport, Vec<i32>) {
+    let started = Instant::now();
+    let deadline = started + SOURCE_TIMEOUT;
+
+    // Spawned tasks run side by side, awaiting them in order just collects the results
+    let handles: Vec<_> = sources.iter()
+        .map(|source| {
+            let app_handle = app_handle.clone();
+            let source = source.clone();
+            let query = query.clone();
+            tauri::async_runtime::spawn(async move {
+                // Each source stops itself at the deadline, so its run is still recorded and its jobs processed
+                let run = run_source(&app_handle, &source, query.keywords.clone(), query.location.clone(), scheduled, deadline).await;
+                (source, query, run)
+            })
+        })
+        .collect();
+
+    let mut report = SearchAllReport {
+        keywords: query.keywords.clone(),
+        location: query.location.clone(),
+        ..Default::default()
+    };
+    let mut new_job_ids = Vec::new();
+
+    for handle in handles {
+        let (source, query, run) = match handle.await {
+            Ok(result) => result,
+            Err(e) => {
+                report.errors.push(format!("Task join error: {}", e));
+                continue;
+            }
+        };
+
+        let source_report = match run {
+            Ok(run) => {
+                new_job_ids.extend(run.new_job_ids);
+                run.report
+            }
+            Err(e) => SearchReport {
+                source: source.clone(),
+                keywords: query.keywords,
+                location: query.location,
+                errors: vec![e],
+                ..Default::default()
+            },
+        };
+
+        report.jobs_seen += source_report.jobs_seen;
+        report.inserted += source_report.inserted;
+        report.duplicates_skipped += source_report.duplicates_skipped;
+        report.errors.extend(source_report.errors.iter().map(|e| format!("{}: {}", source, e)));
+        report.reports.push(source_report);
+    }
+
+    report.duration_ms = started.elapsed().as_millis() as i64;
+    (report, new_job_ids)
 }
 
 #[tauri::command]
-pub async fn run_jooble_search(app_handle: AppHandle, keywords: String, location: String) -> Result<String, String> {
+pub fn get_search_runs(app_handle: AppHandle, limit: Option<i32>) -> Result<Vec<SearchReport>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        "SELECT source, keywords, location, pages_fetched, jobs_seen, inserted, duplicates_skipped, errors,
+                duration_ms, run_at
+         FROM search_runs
+         ORDER BY id DESC
+         LIMIT ?1"
+    ).map_err(|e| e.to_string())?;
+
+    let runs = stmt.query_map([limit.unwrap_or(50)], |row| {
+        Ok(SearchReport {
+            source: row.get(0)?,
+            keywords: row.get(1)?,
+            location: row.get(2)?,
+            pages_fetched: row.get(3)?,
+            jobs_seen: row.get(4)?,
+            inserted: row.get(5)?,
+            duplicates_skipped: row.get(6)?,
+            errors: serde_json::from_str(&row.get::<_, String>(7)?).unwrap_or_default(),
+            duration_ms: row.get(8)?,
+            run_at: row.get(9)?,
+        })
+    })
+    .map_err(|e| e.to_string())?
+    .collect::<Result<Vec<_>, _>>()
+    .map_err(|e| e.to_string())?;
+
+    Ok(runs)
+}
+
+pub async fn run_source(
+    app_handle: &AppHandle,
+    source: &str,
+    keywords: String,
+    location: String,
+    scheduled: bool,
+    deadline: Instant,
+) -> Result<SourceRun, String> {
+    let name = match source {
+        "indeed" => "Indeed",
+        "jooble" => "Jooble",
+        "feeds" => "job feeds",
+        "greenhouse" => "Greenhouse",
+        "lever" => "Lever",
+        other => return Err(format!("Unknown job source: {}", other)),
+    };
+
+    let run_guard = begin_run(app_handle, source, scheduled)?;
+    let started = Instant::now();
+
+    let mut report = SearchReport {
+        source: source.to_string(),
+        keywords,
+        location,
+        ..Default::default()
+    };
+
+    emit_progress(app_handle, &SearchProgress {
+        source: report.source.clone(),
+        message: format!("Searching {}...", name),
+        ..Default::default()
+    });
+
+    let new_job_ids = match source {
+        "feeds" => run_feeds(app_handle, &mut report, deadline).await,
+        "greenhouse" | "lever" => run_ats(app_handle, &mut report, deadline).await,
+        _ => run_engine_source(app_handle, name, &mut report, deadline).await,
+    };
+
+    report.inserted = new_job_ids.len() as i32;
+    // Engines that don't report what they saw are assumed to have seen only what they inserted
+    report.jobs_seen = report.jobs_seen.max(report.inserted);
+    report.duplicates_skipped = report.jobs_seen - report.inserted;
+    report.duration_ms = started.elapsed().as_millis() as i64;
+
+    let conn = Connection::open(get_db_path(app_handle)).map_err(|e| e.to_string())?;
+    record_search_run(&conn, &mut report).map_err(|e| e.to_string())?;
+    let _ = app_handle.emit_all("search-finished", &report);
+
+    drop(run_guard);
+    Ok(SourceRun { report, new_job_ids })
+}
+
+pub async fn before_deadline<F: Future>(deadline: Instant, future: F) -> Result<F::Output, String> {
+    // Only use around awaits that haven't written anything yet, the rest of the future is dropped
+    time::timeout_at(time::Instant::from_std(deadline), future).await
+        .map_err(|_| format!("Timed out after {} seconds", SOURCE_TIMEOUT.as_secs()))
+}
+
+async fn run_engine_source(app_handle: &AppHandle, name: &'static str, report: &mut SearchReport, deadline: Instant) -> Vec<i32> {
+    let resource = match report.source.as_str() {
+        "jooble" => "resources/joobsearchengine",
+        _ => "resources/indeedsearchengine",
+    };
+    let executable = match app_handle.path_resolver().resolve_resource(resource) {
+        Some(executable) => executable,
+        None => {
+            report.errors.push(format!("Failed to resolve {} search engine executable path", name));
+            return Vec::new();
+        }
+    };
+    info!("{} executable path: {:?}", name, executable);
 
-    let db_path = get_db_path(&app_handle);
-    let jooble_executable = app_handle
-        .path_resolver()
-        .resolve_resource("resources/joobsearchengine")
-        .expect("Failed to resolve Jooble search engine executable path");
-    info!("Jooble executable path: {:?}", jooble_executable);
+    // The engines fetch pages themselves, so the crawl policy is applied around each run instead
+    let policy = match load_policy(app_handle) {
+        Ok(policy) => policy,
+        Err(e) => {
+            report.errors.push(e);
+            return Vec::new();
+        }
+    };
+    if let Err(e) = before_deadline(deadline, check_engine_robots(app_handle, &policy, report)).await.and_then(|checked| checked) {
+        report.errors.push(e);
+        return Vec::new();
+    }
+
+    let db_path = get_db_path(app_handle);
+    let app_handle = app_handle.clone();
+    let mut engine_report = report.clone();
 
     // Run the search in a separate thread
     let result = task::spawn_blocking(move || {
-        info!("Executing Jooble search engine...");
-        let output = Command::new(&jooble_executable)
-            .arg(db_path.to_str().unwrap())
-            .arg(&keywords)
-            .arg(&location)
-            .output()
-            .map_err(|e| {
-                let error_msg = format!("Failed to execute Jooble search engine: {}", e);
-                error!("{}", error_msg);
-                error_msg
-            })?;
-
-        if !output.status.success() {
-            let error_msg = format!("Jooble search engine execution failed. Exit code: {:?}\nStderr: {}",
-                output.status.code(),
-                String::from_utf8_lossy(&output.stderr));
-            error!("{}", error_msg);
-            return Err(error_msg);
+        // Retries share the source's deadline, so a source never takes longer than SOURCE_TIMEOUT overall
+        let mut attempt = 0;
+        loop {
+            info!("Executing {} search engine...", name);
+            match run_engine(&app_handle, name, &executable, &db_path, &policy, deadline, &mut engine_report) {
+                Ok(()) => break,
+                Err(error_msg) if attempt < policy.max_retries
+                    && Instant::now() + retry_backoff(&policy, attempt) < deadline => {
+                    info!("{}, retrying", error_msg);
+                    thread::sleep(retry_backoff(&policy, attempt));
+                    attempt += 1;
+                }
+                Err(error_msg) => {
+                    error!("{}", error_msg);
+                    engine_report.errors.push(error_msg);
+                    break;
+                }
+            }
         }
 
-        let success_msg = format!("Jooble search completed successfully. Stdout: {}",
-            String::from_utf8_lossy(&output.stdout));
-        info!("{}", success_msg);
-        Ok(success_msg)
-    }).await.map_err(|e| format!("Task join error: {}", e))?;
+        // The engine may have saved some pages before failing, so process whatever it inserted
+        let new_job_ids = Connection::open(&db_path)
+            .and_then(|conn| process_new_jobs(&conn, Some(&engine_report.source)))
+            .map(|processed| processed.into_iter().map(|(id, _)| id).collect())
+            .unwrap_or_else(|e| {
+                engine_report.errors.push(e.to_string());
+                Vec::new()
+            });
+
+        (engine_report, new_job_ids)
+    }).await;
+
+    match result {
+        Ok((engine_report, new_job_ids)) => {
+            *report = engine_report;
+            new_job_ids
+        }
+        Err(e) => {
+            report.errors.push(format!("Task join error: {}", e));
+            Vec::new()
+        }
+    }
+}
+
+async fn check_engine_robots(app_handle: &AppHandle, policy: &CrawlPolicy, report: &SearchReport) -> Result<(), String> {
+    let (base, params) = match report.source.as_str() {
+        "jooble" => ("https://jooble.org/SearchResult", [("ukw", &report.keywords), ("rgns", &report.location)]),
+        _ => ("https://www.indeed.com/jobs", [("q", &report.keywords), ("l", &report.location)]),
+    };
+    let url = Url::parse_with_params(base, &params).map_err(|e| e.to_string())?;
+    let crawler = app_handle.state::<Crawler>();
+    check_robots(&crawler, &http_client(policy)?, &url, policy).await
+}
+
+fn record_search_run(conn: &Connection, report: &mut SearchReport) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO search_runs (source, keywords, location, duration_ms, pages_fetched, jobs_seen, inserted,
+             duplicates_skipped, errors)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            report.source,
+            report.keywords,
+            report.location,
+            report.duration_ms,
+            report.pages_fetched,
+            report.jobs_seen,
+            report.inserted,
+            report.duplicates_skipped,
+            serde_json::to_string(&report.errors).unwrap_or_else(|_| "[]".to_string())
+        ],
+    )?;
+
+    report.run_at = conn.query_row(
+        "SELECT run_at FROM search_runs WHERE id = ?1",
+        [conn.last_insert_rowid()],
+        |row| row.get(0),
+    )?;
+    Ok(())
+}
 
-    result
+pub fn emit_progress(app_handle: &AppHandle, progress: &SearchProgress) {
+    let _ = app_handle.emit_all("search-progress", progress);
 }
 
+fn run_engine(
+    app_handle: &AppHandle,
+    name: &str,
+    executable: &Path,
+    db_path: &Path,
+    policy: &CrawlPolicy,
+    deadline: Instant,
+    report: &mut SearchReport,
+) -> Result<(), String> {
+    let mut child = Command::new(executable)
+        .arg(db_path.to_str().unwrap())
+        .arg(&report.keywords)
+        .arg(&report.location)
+        .env("SOLICIT_USER_AGENT", &policy.user_agent)
+        .env("SOLICIT_DELAY_MS", policy.delay_ms.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to execute {} search engine: {}", name, e))?;
+
+    // Drain stderr on its own thread so a chatty engine can't block on a full pipe
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut stderr = child.stderr.take().unwrap();
+    let stderr_reader = thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stderr.read_to_string(&mut output);
+        output
+    });
+
+    // The watchdog owns the process and kills it once the timeout passes, which also ends the stdout loop
+    let watchdog = thread::spawn(move || {
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) => return Ok(Some(status)),
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Ok(None);
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(200)),
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+    });
+
+    let mut progress = SearchProgress {
+        source: report.source.clone(),
+        ..Default::default()
+    };
+    for line in stdout.lines().map_while(Result::ok) {
+        if read_engine_line(&mut progress, &line) {
+            progress.message = line.trim().to_string();
+            emit_progress(app_handle, &progress);
+        }
+    }
+
+    let status = watchdog.join().map_err(|_| "Search engine watchdog panicked".to_string())?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+
+    report.pages_fetched = progress.page;
+    report.jobs_seen = progress.jobs_found;
+
+    let status = match status? {
+        Some(status) => status,
+        None => return Err(format!("{} search timed out after {} seconds", name, SOURCE_TIMEOUT.as_secs())),
+    };
+
+    if !status.success() {
+        return Err(format!("{} search engine execution failed. Exit code: {:?}\nStderr: {}",
+            name,
+            status.code(),
+            stderr));
+    }
+    Ok(())
+}
+
+fn read_engine_line(progress: &mut SearchProgress, line: &str) -> bool {
+    // The engines log lines such as "Fetching page 3 of 5" and "Found 15 jobs", pick the totals out of those.
+    // Job counts are running totals, so the largest one seen wins rather than adding them up
+    let lower = line.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    let mut changed = false;
+    for (i, word) in words.iter().enumerate() {
+        let number = match word.parse::<i32>() {
+            Ok(number) => number,
+            Err(_) => continue,
+        };
+        let before = if i > 0 { words[i - 1] } else { "" };
+        let after = words.get(i + 1).copied().unwrap_or("");
+
+        if before == "page" {
+            progress.page = progress.page.max(number);
+            changed = true;
+        } else if before == "of" && i >= 3 && words[i - 3] == "page" {
+            progress.total_pages = Some(number);
+        } else if after.starts_with("job") || after.starts_with("result") || after.starts_with("listing") {
+            progress.jobs_found = progress.jobs_found.max(number);
+            changed = true;
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn engine_lines_update_progress() {
+        let mut progress = SearchProgress::default();
+        assert!(read_engine_line(&mut progress, "Fetching page 2 of 5"));
+        assert_eq!(progress.page, 2);
+        assert_eq!(progress.total_pages, Some(5));
+
+        assert!(!read_engine_line(&mut progress, "Connecting to the database"));
+        assert_eq!(progress.page, 2);
+    }
+
+    #[test]
+    fn cumulative_job_counts_are_not_added_up() {
+        let mut progress = SearchProgress::default();
+        read_engine_line(&mut progress, "Found 15 jobs");
+        read_engine_line(&mut progress, "Found 30 jobs");
+        read_engine_line(&mut progress, "Page 3: 30 results");
+        assert_eq!(progress.jobs_found, 30);
+    }
+
+    #[tokio::test]
+    async fn work_past_the_deadline_is_cut_off() {
+        let deadline = Instant::now() + Duration::from_millis(50);
+        assert_eq!(before_deadline(deadline, async { 1 }).await, Ok(1));
+
+        let result = before_deadline(deadline, time::sleep(Duration::from_secs(5))).await;
+        assert_eq!(result, Err(format!("Timed out after {} seconds", SOURCE_TIMEOUT.as_secs())));
+    }
+}
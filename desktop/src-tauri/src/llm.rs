@@ -1,4 +1,5 @@
-use crate::schemas::ParsedDetails;
+use crate::schemas::CvDetails;
+use crate::cv::{parse_backend_cv, save_cv_details};
 
 use reqwest::Client;
 use serde_json::{json, Value};
@@ -6,6 +7,9 @@ use std::path::PathBuf;
 use tauri::AppHandle;
 use std::fs;
 
+const ANTHROPIC_MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_MODEL: &str = "claude-3-5-sonnet-20240620";
+
 #[tauri::command]
 pub fn get_key(app_handle: AppHandle) -> Result<String, String> {
     let file_path = get_credentials_file_path(&app_handle);
@@ -15,10 +19,10 @@ pub fn get_key(app_handle: AppHandle) -> Result<String, String> {
     }
 
     let contents = fs::read_to_string(file_path)
-        .map_err(|_e| format!("Failed to read credentials file"))?;
+        .map_err(|_e| "Failed to read credentials file".to_string())?;
 
     let json: Value = serde_json::from_str(&contents)
-        .map_err(|_e| format!("Failed to parse credentials JSON"))?;
+        .map_err(|_e| "Failed to parse credentials JSON".to_string())?;
 
     Ok(json["anthropic_api_key"].as_str().unwrap_or("").to_string())
 }
@@ -37,7 +41,7 @@ pub fn set_key(app_handle: AppHandle, key: String) -> Result<(), String> {
     });
 
     fs::write(file_path.clone(), serde_json::to_string_pretty(&json).unwrap())
-        .map_err(|_e| format!("Failed to write credentials file"))?;
+        .map_err(|_e| "Failed to write credentials file".to_string())?;
     Ok(())
 }
 
@@ -70,8 +74,8 @@ pub async fn suggestions(app_handle: AppHandle, query_details: serde_json::Value
 }
 
 #[tauri::command]
-pub async fn extract_cv(app_handle: AppHandle, preprocessed_text: String) -> Result<ParsedDetails, String> {
-    let api_key = get_key(app_handle)?;
+pub async fn extract_cv(app_handle: AppHandle, preprocessed_text: String) -> Result<CvDetails, String> {
+    let api_key = get_key(app_handle.clone())?;
     let request_body = json!({
         "text": preprocessed_text,
         "anthropic_api_key": api_key
@@ -92,25 +96,55 @@ pub async fn extract_cv(app_handle: AppHandle, preprocessed_text: String) -> Res
 
     let backend_result: serde_json::Value = res.json().await.map_err(|e| e.to_string())?;
 
-    let frontend_result = ParsedDetails {
-        experience: backend_result["Experience"].as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join("\n"))
-            .unwrap_or_default(),
-        interests: backend_result["Interests"].as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join("\n"))
-            .unwrap_or_default(),
-        projects: backend_result["Projects"].as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join("\n"))
-            .unwrap_or_default(),
-        education: backend_result["Education"].as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join("\n"))
-            .unwrap_or_default(),
-        certificates: backend_result["Certificates"].as_array()
-            .map(|arr| arr.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join("\n"))
-            .unwrap_or_default(),
-    };
-
-    Ok(frontend_result)
+    let details = parse_backend_cv(&backend_result);
+    save_cv_details(&app_handle, &details)?;
+
+    Ok(details)
+}
+
+pub async fn ask_for_json(app_handle: &AppHandle, prompt: &str) -> Result<Value, String> {
+    // Used by features the Python backend has no route for, with the same stored key
+    let api_key = get_key(app_handle.clone())?;
+    if api_key.is_empty() {
+        return Err("Add your Anthropic API key on the credentials page first".to_string());
+    }
+
+    let request_body = json!({
+        "model": ANTHROPIC_MODEL,
+        "max_tokens": 1024,
+        "messages": [{"role": "user", "content": prompt}]
+    });
+
+    let client = Client::new();
+    let res = client.post(ANTHROPIC_MESSAGES_URL)
+        .header("x-api-key", api_key)
+        .header("anthropic-version", "2023-06-01")
+        .json(&request_body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let status = res.status();
+    if !status.is_success() {
+        let error_text = res.text().await.map_err(|e| e.to_string())?;
+        return Err(format!("HTTP Error: {}, message: {}", status, error_text));
+    }
+
+    let response_json: Value = res.json().await.map_err(|e| e.to_string())?;
+    let reply = response_json["content"][0]["text"].as_str()
+        .ok_or_else(|| "No text in the LLM response".to_string())?;
+
+    parse_json_reply(reply)
+}
+
+pub fn parse_json_reply(reply: &str) -> Result<Value, String> {
+    // Models like to wrap the object in prose or a code fence, so take the outermost braces
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => serde_json::from_str(&reply[start..=end]).map_err(|e| e.to_string()),
+        _ => Err("The LLM did not reply with JSON".to_string()),
+    }
 }
 
 fn get_credentials_file_path(app_handle: &AppHandle) -> PathBuf {
@@ -119,4 +153,22 @@ fn get_credentials_file_path(app_handle: &AppHandle) -> PathBuf {
         .app_data_dir()
         .unwrap()
         .join("credentials.json")
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_is_taken_out_of_fenced_replies() {
+        let reply = "Here you go:\n```json\n{\"score\": 80, \"matched_skills\": [\"Rust\"]}\n```";
+        let value = parse_json_reply(reply).unwrap();
+        assert_eq!(value["score"], 80);
+        assert_eq!(value["matched_skills"][0], "Rust");
+    }
+
+    #[test]
+    fn replies_without_json_are_errors() {
+        assert!(parse_json_reply("Sorry, I can't help with that").is_err());
+        assert!(parse_json_reply("} backwards {").is_err());
+    }
+}
@@ -0,0 +1,264 @@
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct LocationInfo {
+    pub city: Option<String>,
+    pub region: Option<String>,
+    pub country: Option<String>,
+    pub work_mode: Option<String>,
+}
+
+// Hybrid first, since "hybrid remote in Milano" is not a remote job
+const HYBRID_MARKERS: &[&str] = &["hybrid", "ibrido", "ibrida", "smart working", "partially remote", "flexible working"];
+const REMOTE_MARKERS: &[&str] = &[
+    "remote", "remoto", "da remoto", "work from home", "wfh", "home based", "home-based", "telelavoro",
+    "anywhere", "full remote", "fully remote",
+];
+const ONSITE_MARKERS: &[&str] = &["on-site", "onsite", "on site", "in sede", "in office"];
+
+const COUNTRIES: &[(&str, &[&str])] = &[
+    ("Italy", &["italy", "italia"]),
+    ("United Kingdom", &["united kingdom", "uk", "england", "scotland", "wales", "northern ireland", "great britain", "regno unito"]),
+    ("United States", &["united states", "usa", "us", "stati uniti"]),
+    ("Ireland", &["ireland", "irlanda"]),
+    ("Germany", &["germany", "deutschland", "germania"]),
+    ("France", &["france", "francia"]),
+    ("Spain", &["spain", "españa", "spagna"]),
+    ("Switzerland", &["switzerland", "svizzera", "schweiz", "suisse"]),
+    ("Netherlands", &["netherlands", "the netherlands", "olanda", "paesi bassi"]),
+    ("Canada", &["canada"]),
+    ("Australia", &["australia"]),
+    ("Turkey", &["turkey", "türkiye", "turchia"]),
+];
+
+const ITALIAN_REGIONS: &[&str] = &[
+    "abruzzo", "basilicata", "calabria", "campania", "emilia-romagna", "emilia romagna", "friuli-venezia giulia",
+    "lazio", "liguria", "lombardia", "marche", "molise", "piemonte", "puglia", "sardegna", "sicilia", "toscana",
+    "trentino-alto adige", "umbria", "valle d'aosta", "veneto",
+];
+
+const UK_REGIONS: &[&str] = &[
+    "greater london", "greater manchester", "west midlands", "east midlands", "west yorkshire", "south yorkshire",
+    "merseyside", "tyne and wear", "kent", "surrey", "essex", "hampshire", "berkshire", "oxfordshire",
+    "cambridgeshire", "lancashire", "bristol",
+];
+
+const US_STATES: &[&str] = &[
+    "AL", "AK", "AZ", "AR", "CA", "CO", "CT", "DE", "DC", "FL", "GA", "HI", "ID", "IL", "IN", "IA", "KS", "KY", "LA",
+    "ME", "MD", "MA", "MI", "MN", "MS", "MO", "MT", "NE", "NV", "NH", "NJ", "NM", "NY", "NC", "ND", "OH", "OK", "OR",
+    "PA", "RI", "SC", "SD", "TN", "TX", "UT", "VT", "VA", "WA", "WV", "WI", "WY",
+];
+
+pub fn parse_location(location: &str, title: &str) -> LocationInfo {
+    let lower_all = format!("{} {}", location.to_lowercase(), title_work_mode(title));
+
+    let work_mode = if has_marker(&lower_all, HYBRID_MARKERS) {
+        Some("hybrid")
+    } else if has_marker(&lower_all, REMOTE_MARKERS) {
+        Some("remote")
+    } else if has_marker(&lower_all, ONSITE_MARKERS) {
+        Some("onsite")
+    } else {
+        None
+    };
+
+    let mut info = LocationInfo {
+        work_mode: work_mode.map(str::to_string),
+        ..Default::default()
+    };
+
+    let mut location = strip_work_mode(location);
+    if let Some(province) = italian_province(&location) {
+        // Italian boards write "Milano (MI)", which would otherwise read as Michigan
+        location = location.replace(&format!("({})", province), "");
+        info.region = Some(province);
+        info.country = Some("Italy".to_string());
+    }
+
+    let mut parts: Vec<String> = location
+        .replace(['(', ')'], ",")
+        .split([',', '•', '|'])
+        .map(clean_part)
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    // Countries and well known regions can be recognised wherever they appear
+    parts.retain(|part| {
+        let lower = part.to_lowercase();
+        if let Some((country, _)) = COUNTRIES.iter().find(|(_, aliases)| aliases.contains(&lower.as_str())) {
+            info.country = Some(country.to_string());
+            return false;
+        }
+        if info.region.is_none() && (ITALIAN_REGIONS.contains(&lower.as_str()) || UK_REGIONS.contains(&lower.as_str())) {
+            if info.country.is_none() {
+                let country = if ITALIAN_REGIONS.contains(&lower.as_str()) { "Italy" } else { "United Kingdom" };
+                info.country = Some(country.to_string());
+            }
+            info.region = Some(title_case(part));
+            return false;
+        }
+        if info.region.is_none() && US_STATES.contains(&part.as_str()) {
+            info.region = Some(part.clone());
+            info.country.get_or_insert_with(|| "United States".to_string());
+            return false;
+        }
+        true
+    });
+
+    let mut remaining = parts.into_iter();
+    if let Some(city) = remaining.next() {
+        let city = city.strip_prefix("Provincia di ").unwrap_or(&city).to_string();
+        info.city = Some(city);
+    }
+    if info.region.is_none() {
+        info.region = remaining.next();
+    }
+
+    info
+}
+
+fn title_work_mode(title: &str) -> String {
+    // Titles only say how the job is worked in a part of their own, "Engineer (Remote)" or "Engineer - Remote",
+    // otherwise "Remote Sensing Engineer" would be a remote job
+    title.replace(" - ", "|").replace(" – ", "|").replace(" — ", "|")
+        .split(['(', ')', '[', ']', '|', ',', '/', ':'])
+        .filter(|part| is_work_mode_only(part))
+        .map(|part| part.trim().to_lowercase())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn has_marker(text: &str, markers: &[&str]) -> bool {
+    markers.iter().any(|marker| {
+        text.match_indices(marker).any(|(pos, _)| {
+            let before = text[..pos].chars().next_back();
+            let after = text[pos + marker.len()..].chars().next();
+            !before.map(|c| c.is_alphanumeric()).unwrap_or(false)
+                && !after.map(|c| c.is_alphanumeric()).unwrap_or(false)
+        })
+    })
+}
+
+fn strip_work_mode(location: &str) -> String {
+    // Indeed style "Hybrid remote in Milano, Lombardia" or "Remote in London"
+    // ASCII lowercasing keeps byte offsets valid for slicing the original, to_lowercase can change lengths
+    let lower = location.to_ascii_lowercase();
+    let start = match lower.find(" in ") {
+        Some(pos) if has_marker(&lower[..pos], HYBRID_MARKERS) || has_marker(&lower[..pos], REMOTE_MARKERS) => pos + 4,
+        _ => 0,
+    };
+    let location = &location[start..];
+
+    if is_work_mode_only(location) {
+        return String::new();
+    }
+
+    location.to_string()
+}
+
+fn italian_province(location: &str) -> Option<String> {
+    let start = location.find('(')? + 1;
+    let end = start + location[start..].find(')')?;
+    let code = location[start..end].trim();
+
+    if code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase()) {
+        Some(code.to_string())
+    } else {
+        None
+    }
+}
+
+fn clean_part(part: &str) -> String {
+    // Drop postcodes such as "20121 Milano", "Austin, TX 78701" or "London EC1A"
+    let words: Vec<&str> = part.split_whitespace()
+        .filter(|word| !word.chars().any(|c| c.is_ascii_digit()))
+        .collect();
+
+    let part = words.join(" ");
+    if is_work_mode_only(&part) {
+        return String::new();
+    }
+
+    part
+}
+
+fn is_work_mode_only(text: &str) -> bool {
+    let lower = text.trim().to_lowercase();
+    HYBRID_MARKERS.iter()
+        .chain(REMOTE_MARKERS)
+        .chain(ONSITE_MARKERS)
+        .any(|marker| lower == *marker)
+}
+
+fn title_case(text: &str) -> String {
+    text.split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indeed_style_locations() {
+        let info = parse_location("Hybrid remote in Milano, Lombardia", "Backend Developer");
+        assert_eq!(info.city.as_deref(), Some("Milano"));
+        assert_eq!(info.region.as_deref(), Some("Lombardia"));
+        assert_eq!(info.country.as_deref(), Some("Italy"));
+        assert_eq!(info.work_mode.as_deref(), Some("hybrid"));
+    }
+
+    #[test]
+    fn italian_provinces_are_not_us_states() {
+        let info = parse_location("20121 Milano (MI)", "");
+        assert_eq!(info.city.as_deref(), Some("Milano"));
+        assert_eq!(info.region.as_deref(), Some("MI"));
+        assert_eq!(info.country.as_deref(), Some("Italy"));
+    }
+
+    #[test]
+    fn us_states_and_postcodes() {
+        let info = parse_location("Austin, TX 78701", "");
+        assert_eq!(info.city.as_deref(), Some("Austin"));
+        assert_eq!(info.region.as_deref(), Some("TX"));
+        assert_eq!(info.country.as_deref(), Some("United States"));
+    }
+
+    #[test]
+    fn work_mode_is_unknown_unless_stated() {
+        assert_eq!(parse_location("London, United Kingdom", "").work_mode, None);
+        assert_eq!(parse_location("London", "Engineer (on-site)").work_mode.as_deref(), Some("onsite"));
+        assert_eq!(parse_location("Remote", "").work_mode.as_deref(), Some("remote"));
+        assert_eq!(parse_location("Remote", "").city, None);
+    }
+
+    #[test]
+    fn remote_in_the_title_needs_a_part_of_its_own() {
+        assert_eq!(parse_location("Bristol", "Remote Sensing Engineer").work_mode, None);
+        assert_eq!(parse_location("", "Data Engineer (Remote)").work_mode.as_deref(), Some("remote"));
+        assert_eq!(parse_location("", "Data Engineer - Remote").work_mode.as_deref(), Some("remote"));
+        assert_eq!(parse_location("", "Data Engineer | Hybrid").work_mode.as_deref(), Some("hybrid"));
+        assert_eq!(parse_location("", "Data Engineer [On-site]").work_mode.as_deref(), Some("onsite"));
+        assert_eq!(parse_location("", "Fully Remote Team Lead").work_mode, None);
+    }
+
+    #[test]
+    fn non_ascii_locations_keep_their_characters() {
+        let info = parse_location("İstanbul remote in Turkey", "");
+        assert_eq!(info.country.as_deref(), Some("Turkey"));
+        assert_eq!(info.work_mode.as_deref(), Some("remote"));
+
+        let info = parse_location("İ remote in öx", "");
+        assert_eq!(info.city.as_deref(), Some("öx"));
+
+        let info = parse_location("Zürich, Schweiz", "");
+        assert_eq!(info.city.as_deref(), Some("Zürich"));
+        assert_eq!(info.country.as_deref(), Some("Switzerland"));
+    }
+}
@@ -1,13 +1,39 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod llm;
+mod cv;
+mod resume;
+mod database;
+mod matching;
+mod html;
+mod jobdetails;
+mod jobentry;
+mod jobexport;
+mod backup;
+mod jobnotes;
+mod companies;
+mod rules;
+mod archive;
+mod reminders;
+mod calendar;
+mod crawler;
+mod requirements;
+mod salary;
+mod ingest;
+mod location;
+mod searches;
+mod notifications;
+mod tray;
+mod settings;
 mod server;
 mod schemas;
 mod helpers;
 mod appconfig;
 mod jobsearch;
+mod feeds;
+mod ats;
 
-use tauri::Manager;
+use tauri::{AppHandle, Manager};
 use rusqlite::Connection;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
@@ -16,7 +42,29 @@ use simplelog::{LevelFilter, CombinedLogger, Config, TermLogger, WriteLogger, Te
 
 use helpers::get_db_path;
 use llm::{set_key, get_key, suggestions, extract_cv};
-use jobsearch::{run_indeed_search, run_jooble_search};
+use cv::{read_cv_details, write_cv_details, flatten_cv_details};
+use resume::{import_profile, export_profile};
+use matching::{score_jobs, score_job};
+use jobdetails::{fetch_job_details, use_job_description};
+use jobentry::{add_job, import_job_from_url};
+use jobexport::{export_jobs, import_jobs};
+use backup::{create_backup, restore_backup};
+use jobnotes::{list_job_notes, add_job_note, update_job_note, delete_job_note, list_tags, set_job_tags, rename_tag, delete_tag,
+    list_job_contacts, add_job_contact, update_job_contact, delete_job_contact};
+use companies::{list_companies, add_company, update_company, set_company_blocked, get_blacklist_settings, set_blacklist_settings};
+use rules::{list_rules, add_rule, update_rule, delete_rule, preview_rule};
+use archive::{get_archive_settings, set_archive_settings, archive_stale_jobs, purge_archived};
+use reminders::{list_reminders, add_reminder, update_reminder, set_reminder_done, delete_reminder, export_reminders, export_calendar};
+use crawler::{Crawler, get_crawl_policy, set_crawl_policy};
+use requirements::extract_requirements;
+use database::migrate;
+use ingest::process_new_jobs;
+use searches::{Scheduler, start_scheduler, list_saved_searches, create_saved_search, update_saved_search, delete_saved_search, run_saved_search};
+use notifications::{NewJobsBadge, get_notification_settings, set_notification_settings, clear_new_jobs_badge};
+use tray::{build_tray, handle_tray_event, handle_window_event, set_scheduler_paused, is_scheduler_paused};
+use jobsearch::{run_indeed_search, run_jooble_search, search_all, get_search_runs};
+use feeds::{list_feeds, add_feed, set_feed_enabled, delete_feed};
+use ats::{get_ats_boards, set_ats_boards};
 use server::{start_api_server, get_unread_jobs, update_job, get_stats};
 use appconfig::{initialise_config, read_config, write_config, write_job_description, read_job_description, read_applicant_details, write_applicant_details};
 
@@ -44,6 +92,9 @@ fn main() {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_window_state::Builder::default().build())
+        .system_tray(build_tray())
+        .on_system_tray_event(handle_tray_event)
+        .on_window_event(handle_window_event)
         .setup(|app| {
             let window = app.get_window("main").unwrap();
             set_shadow(&window, true).expect("Unsupported platform!");
@@ -54,18 +105,27 @@ fn main() {
             copy_database(app)?;
 
             let db_path = get_db_path(&app.handle());
-            let _conn = Connection::open(&db_path).expect("Failed to open database");
+            let conn = Connection::open(&db_path).expect("Failed to open database");
+            migrate(&conn).expect("Failed to migrate database");
+            process_new_jobs(&conn, None).expect("Failed to process new jobs");
 
             initialise_config(app.handle()).unwrap();
 
             // Start the API server
             start_api_server(&app.handle()).expect("Failed to start API server");
 
+            app.manage(Crawler::default());
+            app.manage(NewJobsBadge::default());
+            app.manage(Scheduler::default());
+            start_scheduler(app.handle());
+
             Ok::<(), Box<dyn std::error::Error>>(())
         })
         .invoke_handler(tauri::generate_handler![
             run_indeed_search,
             run_jooble_search,
+            search_all,
+            get_search_runs,
             write_job_description,
             read_job_description,
             write_applicant_details,
@@ -79,6 +139,74 @@ fn main() {
             set_key,
             suggestions,
             extract_cv,
+            read_cv_details,
+            write_cv_details,
+            flatten_cv_details,
+            import_profile,
+            export_profile,
+            score_jobs,
+            score_job,
+            fetch_job_details,
+            use_job_description,
+            add_job,
+            import_job_from_url,
+            export_jobs,
+            import_jobs,
+            create_backup,
+            restore_backup,
+            list_job_notes,
+            add_job_note,
+            update_job_note,
+            delete_job_note,
+            list_tags,
+            set_job_tags,
+            rename_tag,
+            delete_tag,
+            list_job_contacts,
+            add_job_contact,
+            update_job_contact,
+            delete_job_contact,
+            list_companies,
+            add_company,
+            update_company,
+            set_company_blocked,
+            get_blacklist_settings,
+            set_blacklist_settings,
+            list_rules,
+            add_rule,
+            update_rule,
+            delete_rule,
+            preview_rule,
+            get_archive_settings,
+            set_archive_settings,
+            archive_stale_jobs,
+            purge_archived,
+            list_reminders,
+            add_reminder,
+            update_reminder,
+            set_reminder_done,
+            delete_reminder,
+            export_reminders,
+            export_calendar,
+            extract_requirements,
+            list_saved_searches,
+            create_saved_search,
+            update_saved_search,
+            delete_saved_search,
+            run_saved_search,
+            get_notification_settings,
+            set_notification_settings,
+            clear_new_jobs_badge,
+            set_scheduler_paused,
+            is_scheduler_paused,
+            list_feeds,
+            add_feed,
+            set_feed_enabled,
+            delete_feed,
+            get_ats_boards,
+            set_ats_boards,
+            get_crawl_policy,
+            set_crawl_policy,
             quit_app
         ])
         .run(tauri::generate_context!())
@@ -86,8 +214,8 @@ fn main() {
 }
 
 #[tauri::command]
-fn quit_app() {
-    std::process::exit(0);
+fn quit_app(app_handle: AppHandle) {
+    app_handle.exit(0);
 }
 
 fn copy_database(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
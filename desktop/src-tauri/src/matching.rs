@@ -0,0 +1,407 @@
+use crate::schemas::{Job, CvDetails, MatchResult, ParsedDetails};
+use crate::server::{job_from_row, JOB_COLUMNS};
+use crate::helpers::get_db_path;
+use crate::appconfig::read_applicant_details;
+use crate::cv::read_cv_details;
+use crate::llm::ask_for_json;
+
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use std::collections::BTreeSet;
+use log::{info, error};
+use tauri::AppHandle;
+
+const STOPWORDS: &[&str] = &[
+    "and", "the", "for", "with", "our", "you", "your", "are", "will", "from", "this", "that",
+    "who", "have", "has", "not", "all", "can", "but", "into", "per", "via", "etc",
+    "job", "role", "team", "work", "working", "position", "candidate", "company", "opportunity",
+    "senior", "junior", "mid", "lead", "level", "full", "time", "part", "permanent", "contract",
+    "experience", "years", "year", "month", "salary", "benefits", "new", "looking", "join",
+    "skills", "strong", "excellent", "good", "solid", "knowledge", "understanding", "ability", "proven",
+];
+
+pub struct Profile {
+    skills: Vec<String>,
+    tokens: BTreeSet<String>,
+    text: String,
+}
+
+impl Profile {
+    pub fn load(app_handle: &AppHandle) -> Result<Profile, String> {
+        let applicant_details: Value = serde_json::from_str(&read_applicant_details(app_handle.clone())?)
+            .map_err(|e| e.to_string())?;
+        let cv_details = read_cv_details(app_handle.clone())?;
+
+        Ok(Profile::new(&applicant_details, &cv_details))
+    }
+
+    pub fn new(applicant_details: &Value, cv_details: &CvDetails) -> Profile {
+        // A CV extracted by the backend only has free-text sections, so those are the profile,
+        // with listed skills, project technologies and certificates as the phrases to look for
+        let flat = ParsedDetails::from(cv_details);
+        let mut text = ["experience", "interests", "projects", "education", "certificates"].iter()
+            .filter_map(|field| applicant_details[*field].as_str())
+            .chain([&flat.experience, &flat.interests, &flat.projects, &flat.education, &flat.certificates].map(String::as_str))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let mut skills = Vec::new();
+        let names = cv_details.skills.iter()
+            .flat_map(|skill| std::iter::once(&skill.name).chain(skill.keywords.iter()))
+            .chain(cv_details.projects.iter().flat_map(|project| project.highlights.iter()))
+            .chain(cv_details.certificates.iter().map(|certificate| &certificate.name));
+        for name in names {
+            // Highlights can be whole sentences, only short ones are skill names
+            if !name.is_empty() && name.split_whitespace().count() <= 4 && !skills.contains(name) {
+                skills.push(name.clone());
+            }
+        }
+        text.push('\n');
+        text.push_str(&skills.join("\n"));
+
+        Profile {
+            tokens: significant_tokens(&text).into_iter().collect(),
+            skills,
+            text,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    pub fn score(&self, title: &str, description: &str, must_have: &[String]) -> MatchResult {
+        // Only skills listed on the CV count as matches, shared everyday words in the description don't
+        let job_text = format!(" {} ", tokenise(&format!("{}\n{}", title, description)).join(" "));
+        let skill_phrases: Vec<String> = self.skills.iter().map(|skill| tokenise(skill).join(" ")).collect();
+
+        let mut matched_skills: Vec<String> = self.skills.iter()
+            .zip(skill_phrases.iter())
+            .filter(|(_, phrase)| !phrase.is_empty() && job_text.contains(&format!(" {} ", phrase)))
+            .map(|(skill, _)| skill.clone())
+            .collect();
+        let mut missing_skills = Vec::new();
+
+        for term in significant_tokens(title) {
+            // Description words are mostly noise either way, title words are worth counting
+            if matched_skills.iter().any(|skill| skill.eq_ignore_ascii_case(&term)) {
+                continue;
+            }
+            if self.tokens.contains(&term) {
+                matched_skills.push(term);
+            } else {
+                missing_skills.push(term);
+            }
+        }
+
+        for requirement in must_have {
+            // Requirements can be whole sentences ("5+ years of Rust or Go"), so look for skills inside them
+            let phrase = format!(" {} ", tokenise(requirement).join(" "));
+            let contains = |skill: &String| {
+                let skill_phrase = tokenise(skill).join(" ");
+                !skill_phrase.is_empty() && phrase.contains(&format!(" {} ", skill_phrase))
+            };
+            let counted = matched_skills.iter().any(contains)
+                || missing_skills.iter().any(|s| s.eq_ignore_ascii_case(requirement.trim()));
+            if phrase.trim().is_empty() || counted {
+                continue;
+            }
+
+            let mut found: Vec<String> = self.skills.iter().filter(|skill| contains(skill)).cloned().collect();
+            if found.is_empty() {
+                found = significant_tokens(requirement).into_iter()
+                    .filter(|token| self.tokens.contains(token))
+                    .collect();
+            }
+            if found.is_empty() {
+                missing_skills.push(requirement.trim().to_string());
+            }
+            for skill in found {
+                if !matched_skills.iter().any(|s| s.eq_ignore_ascii_case(&skill)) {
+                    matched_skills.push(skill);
+                }
+            }
+        }
+
+        let considered = matched_skills.len() + missing_skills.len();
+        let score = if considered == 0 {
+            0.0
+        } else {
+            100.0 * matched_skills.len() as f64 / considered as f64
+        };
+
+        MatchResult { score, matched_skills, missing_skills }
+    }
+}
+
+#[tauri::command]
+pub async fn score_jobs(app_handle: AppHandle, use_llm: Option<bool>) -> Result<usize, String> {
+    let profile = Profile::load(&app_handle)?;
+    if profile.is_empty() {
+        return Err("Add your applicant details before scoring jobs".to_string());
+    }
+
+    let db_path = get_db_path(&app_handle);
+    let jobs = {
+        let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+        let mut stmt = conn.prepare(
+            &format!("SELECT {} FROM jobs WHERE read = 0", JOB_COLUMNS)
+        ).map_err(|e| e.to_string())?;
+        let jobs = stmt.query_map([], job_from_row)
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        jobs
+    };
+
+    let mut results = Vec::with_capacity(jobs.len());
+    for job in &jobs {
+        results.push((job.id, score_with_profile(&app_handle, &profile, job, use_llm.unwrap_or(false)).await));
+    }
+
+    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    for (job_id, result) in &results {
+        store_match(&conn, *job_id, result)?;
+    }
+
+    info!("Scored {} unread jobs", results.len());
+    Ok(results.len())
+}
+
+#[tauri::command]
+pub async fn score_job(app_handle: AppHandle, job_id: i32, use_llm: Option<bool>) -> Result<Job, String> {
+    let profile = Profile::load(&app_handle)?;
+    let db_path = get_db_path(&app_handle);
+
+    let job = {
+        let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+        conn.query_row(
+            &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
+            [job_id],
+            job_from_row,
+        ).map_err(|e| e.to_string())?
+    };
+
+    let result = score_with_profile(&app_handle, &profile, &job, use_llm.unwrap_or(false)).await;
+
+    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    store_match(&conn, job_id, &result)?;
+
+    conn.query_row(
+        &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
+        [job_id],
+        job_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+pub fn store_match(conn: &Connection, job_id: i32, result: &MatchResult) -> Result<(), String> {
+    conn.execute(
+        "UPDATE jobs SET match_score = ?1, matched_skills = ?2, missing_skills = ?3 WHERE id = ?4",
+        params![
+            result.score,
+            serde_json::to_string(&result.matched_skills).map_err(|e| e.to_string())?,
+            serde_json::to_string(&result.missing_skills).map_err(|e| e.to_string())?,
+            job_id
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+async fn score_with_profile(app_handle: &AppHandle, profile: &Profile, job: &Job, use_llm: bool) -> MatchResult {
+    let must_have = job.extracted_requirements.as_ref()
+        .map(|requirements| requirements.must_have.clone())
+        .unwrap_or_default();
+    let keyword_result = profile.score(&job.title, &job.description, &must_have);
+
+    if !use_llm {
+        return keyword_result;
+    }
+
+    match llm_score(app_handle, profile, job).await {
+        Ok(llm_result) => merge(keyword_result, llm_result),
+        Err(e) => {
+            error!("LLM scoring failed for job {}, using keyword score: {}", job.id, e);
+            keyword_result
+        }
+    }
+}
+
+async fn llm_score(app_handle: &AppHandle, profile: &Profile, job: &Job) -> Result<MatchResult, String> {
+    let prompt = format!(
+        "Score how well this applicant fits the job from 0 to 100. Reply with only a JSON object with the keys \
+         \"score\" (number), \"matched_skills\" and \"missing_skills\" (arrays of short skill names).\n\n\
+         Job title: {}\nCompany: {}\nJob description:\n{}\n\nApplicant details:\n{}",
+        job.title, job.company, job.description, profile.text
+    );
+    let reply = ask_for_json(app_handle, &prompt).await?;
+    serde_json::from_value(reply).map_err(|e| e.to_string())
+}
+
+fn merge(keyword_result: MatchResult, llm_result: MatchResult) -> MatchResult {
+    let mut matched_skills = llm_result.matched_skills;
+    for skill in keyword_result.matched_skills {
+        if !matched_skills.iter().any(|s| s.eq_ignore_ascii_case(&skill)) {
+            matched_skills.push(skill);
+        }
+    }
+
+    let missing_skills = llm_result.missing_skills.into_iter()
+        .filter(|skill| !matched_skills.iter().any(|s| s.eq_ignore_ascii_case(skill)))
+        .collect();
+
+    MatchResult {
+        score: (keyword_result.score + llm_result.score.clamp(0.0, 100.0)) / 2.0,
+        matched_skills,
+        missing_skills,
+    }
+}
+
+fn tokenise(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !(c.is_alphanumeric() || c == '+' || c == '#' || c == '.'))
+        .map(|token| token.trim_matches('.'))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn significant_tokens(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for token in tokenise(text) {
+        let short_skill = token.contains(['+', '#']) || token == "go" || token == "r" || token == "ai";
+        if (token.chars().count() < 3 && !short_skill)
+            || token.chars().all(|c| c.is_ascii_digit())
+            || STOPWORDS.contains(&token.as_str())
+        {
+            continue;
+        }
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::{Skill, ExperienceEntry, Project, Certificate};
+    use serde_json::json;
+
+    fn profile(skills: &[&str]) -> Profile {
+        let cv_details = CvDetails {
+            skills: skills.iter().map(|name| Skill { name: name.to_string(), ..Default::default() }).collect(),
+            ..Default::default()
+        };
+        Profile::new(&json!({"experience": "Backend developer building services"}), &cv_details)
+    }
+
+    #[test]
+    fn description_words_only_match_as_skills() {
+        // "building" and "services" are in the profile too, but only skills count from the description
+        let result = profile(&["Rust", "PostgreSQL"]).score(
+            "Backend Developer",
+            "You will be building services with Rust and talking to customers",
+            &[],
+        );
+        assert_eq!(result.matched_skills, vec!["Rust".to_string(), "backend".to_string(), "developer".to_string()]);
+        assert!(result.missing_skills.is_empty());
+        assert_eq!(result.score, 100.0);
+    }
+
+    #[test]
+    fn unmatched_must_haves_are_missing() {
+        let result = profile(&["Rust"]).score("Rust", "Rust and Kubernetes", &["Kubernetes".to_string(), "rust".to_string()]);
+        assert_eq!(result.matched_skills, vec!["Rust".to_string()]);
+        assert_eq!(result.missing_skills, vec!["Kubernetes".to_string()]);
+        assert_eq!(result.score, 50.0);
+    }
+
+    #[test]
+    fn backend_cv_without_skills_can_be_scored() {
+        // /api/cv never returns skills, only free-text sections
+        let cv_details = CvDetails {
+            experience: vec![ExperienceEntry { role: "Backend Developer, Acme (2019 - 2023)".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+        let profile = Profile::new(&json!({}), &cv_details);
+        assert!(!profile.is_empty());
+
+        let result = profile.score("Backend Developer", "", &[]);
+        assert_eq!(result.matched_skills, vec!["backend".to_string(), "developer".to_string()]);
+        assert_eq!(result.score, 100.0);
+    }
+
+    #[test]
+    fn project_technologies_and_certificates_are_skills() {
+        let cv_details = CvDetails {
+            projects: vec![Project {
+                name: "notes".to_string(),
+                highlights: vec!["Rust".to_string(), "Built a sync engine used by thousands of people".to_string()],
+                ..Default::default()
+            }],
+            certificates: vec![Certificate { name: "Kubernetes".to_string(), ..Default::default() }],
+            ..Default::default()
+        };
+        let profile = Profile::new(&json!({}), &cv_details);
+        let result = profile.score("Engineer", "Rust services on Kubernetes, used by thousands", &[]);
+        assert_eq!(result.matched_skills, vec!["Rust".to_string(), "Kubernetes".to_string()]);
+    }
+
+    #[test]
+    fn empty_profile_is_empty() {
+        assert!(Profile::new(&json!({}), &CvDetails::default()).is_empty());
+    }
+
+    #[test]
+    fn llm_score_is_averaged_and_skills_merged() {
+        let keyword_result = MatchResult {
+            score: 50.0,
+            matched_skills: vec!["Rust".to_string()],
+            missing_skills: vec!["Kubernetes".to_string()],
+        };
+        let llm_result = MatchResult {
+            score: 130.0,
+            matched_skills: vec!["rust".to_string(), "Kubernetes".to_string()],
+            missing_skills: vec!["kubernetes".to_string(), "Go".to_string()],
+        };
+        let merged = merge(keyword_result, llm_result);
+        assert_eq!(merged.score, 75.0);
+        assert_eq!(merged.matched_skills, vec!["rust".to_string(), "Kubernetes".to_string()]);
+        assert_eq!(merged.missing_skills, vec!["Go".to_string()]);
+    }
+
+    #[test]
+    fn sentence_requirements_match_the_skills_inside_them() {
+        let must_have = vec![
+            "5+ years of Rust or Go".to_string(),
+            "Experience with PostgreSQL".to_string(),
+            "Strong Terraform knowledge".to_string(),
+        ];
+        let result = profile(&["Rust", "PostgreSQL"]).score("", "", &must_have);
+        assert_eq!(result.matched_skills, vec!["Rust".to_string(), "PostgreSQL".to_string()]);
+        assert_eq!(result.missing_skills, vec!["Strong Terraform knowledge".to_string()]);
+    }
+
+    #[test]
+    fn requirements_already_matched_in_the_description_count_once() {
+        let must_have = vec!["Commercial Rust experience".to_string()];
+        let result = profile(&["Rust"]).score("", "We write Rust", &must_have);
+        assert_eq!(result.matched_skills, vec!["Rust".to_string()]);
+        assert!(result.missing_skills.is_empty());
+    }
+
+    #[test]
+    fn requirements_can_match_free_text_experience() {
+        let result = profile(&[]).score("", "", &["Experience building services in Go".to_string()]);
+        assert_eq!(result.matched_skills, vec!["building".to_string(), "services".to_string()]);
+    }
+
+    #[test]
+    fn title_terms_outside_the_profile_are_missing() {
+        let result = profile(&["Rust"]).score("Rust Sales Engineer", "", &[]);
+        assert_eq!(result.matched_skills, vec!["Rust".to_string()]);
+        assert_eq!(result.missing_skills, vec!["sales".to_string(), "engineer".to_string()]);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::schemas::{NotificationSettings, SavedSearch};
+use crate::settings::{get_setting, set_setting};
+use crate::helpers::get_db_path;
+
+use tauri::api::notification::Notification;
+use tauri::{AppHandle, Manager};
+use rusqlite::Connection;
+use std::sync::atomic::{AtomicI32, Ordering};
+use log::{info, error};
+
+#[derive(Default)]
+pub struct NewJobsBadge {
+    unseen: AtomicI32,
+}
+
+#[tauri::command]
+pub fn get_notification_settings(app_handle: AppHandle) -> Result<NotificationSettings, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    get_setting(&conn, "notifications")
+}
+
+#[tauri::command]
+pub fn set_notification_settings(app_handle: AppHandle, settings: NotificationSettings) -> Result<(), String> {
+    for time in [&settings.quiet_hours_start, &settings.quiet_hours_end].into_iter().flatten() {
+        if parse_minutes(time).is_none() {
+            return Err(format!("Quiet hours must look like 22:00, got {}", time));
+        }
+    }
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    set_setting(&conn, "notifications", &settings)
+}
+
+#[tauri::command]
+pub fn clear_new_jobs_badge(app_handle: AppHandle) {
+    app_handle.state::<NewJobsBadge>().unseen.store(0, Ordering::SeqCst);
+    update_badge(&app_handle, 0);
+}
+
+pub fn notify_new_jobs(app_handle: &AppHandle, search: &SavedSearch, new_jobs: i32) {
+    if new_jobs <= 0 {
+        return;
+    }
+
+    let unseen = app_handle.state::<NewJobsBadge>().unseen.fetch_add(new_jobs, Ordering::SeqCst) + new_jobs;
+    update_badge(app_handle, unseen);
+
+    if !search.notify {
+        return;
+    }
+
+    if !notifications_allowed(app_handle) {
+        info!("Skipping notification for saved search {}", search.id);
+        return;
+    }
+
+    let body = if new_jobs == 1 {
+        format!("1 new job for \"{}\"", search.name)
+    } else {
+        format!("{} new jobs for \"{}\"", new_jobs, search.name)
+    };
+    show_notification(app_handle, "New jobs found", &body);
+}
+
+pub fn notifications_allowed(app_handle: &AppHandle) -> bool {
+    let settings: NotificationSettings = match Connection::open(get_db_path(app_handle))
+        .map_err(|e| e.to_string())
+        .and_then(|conn| get_setting(&conn, "notifications"))
+    {
+        Ok(settings) => settings,
+        Err(e) => {
+            error!("Failed to load notification settings: {}", e);
+            return false;
+        }
+    };
+
+    !settings.disabled && !in_quiet_hours(app_handle, &settings)
+}
+
+pub fn show_notification(app_handle: &AppHandle, title: &str, body: &str) -> bool {
+    match Notification::new(&app_handle.config().tauri.bundle.identifier)
+        .title(title)
+        .body(body)
+        .show()
+    {
+        Ok(()) => true,
+        Err(e) => {
+            error!("Failed to show notification: {}", e);
+            false
+        }
+    }
+}
+
+fn update_badge(app_handle: &AppHandle, unseen: i32) {
+    // Tauri has no dock badge API, so the count goes next to the tray icon instead (macOS only shows the title)
+    let tray = app_handle.tray_handle();
+    let (title, tooltip) = if unseen > 0 {
+        (unseen.to_string(), format!("Solicit - {} new jobs", unseen))
+    } else {
+        (String::new(), "Solicit".to_string())
+    };
+
+    if let Err(e) = tray.set_title(&title).and_then(|_| tray.set_tooltip(&tooltip)) {
+        error!("Failed to update tray badge: {}", e);
+    }
+}
+
+fn in_quiet_hours(app_handle: &AppHandle, settings: &NotificationSettings) -> bool {
+    let (start, end) = match (&settings.quiet_hours_start, &settings.quiet_hours_end) {
+        (Some(start), Some(end)) => match (parse_minutes(start), parse_minutes(end)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return false,
+        },
+        _ => return false,
+    };
+
+    // SQLite already knows the local time zone, which saves pulling in a date crate
+    let now: Option<String> = Connection::open(get_db_path(app_handle))
+        .and_then(|conn| conn.query_row("SELECT strftime('%H:%M', 'now', 'localtime')", [], |row| row.get(0)))
+        .ok();
+    let now = match now.as_deref().and_then(parse_minutes) {
+        Some(now) => now,
+        None => return false,
+    };
+
+    if start <= end {
+        now >= start && now < end
+    } else {
+        // Wraps past midnight, e.g. 22:00 to 07:00
+        now >= start || now < end
+    }
+}
+
+fn parse_minutes(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    if hours < 24 && minutes < 60 {
+        Some(hours * 60 + minutes)
+    } else {
+        None
+    }
+}
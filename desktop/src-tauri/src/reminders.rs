@@ -0,0 +1,372 @@
+use crate::schemas::JobReminder;
+use crate::calendar::{write_calendar, CalendarEvent};
+use crate::notifications::{notifications_allowed, show_notification};
+use crate::helpers::get_db_path;
+
+use rusqlite::{params, Connection};
+use tauri::{AppHandle, Manager};
+use std::fs;
+use log::{info, error};
+
+const REMINDER_KINDS: &[&str] = &["follow_up", "interview", "deadline"];
+
+const REMINDER_COLUMNS: &str = "reminders.id, reminders.job_id, reminders.kind, reminders.title, reminders.due_at,
+    reminders.duration_minutes, reminders.location, reminders.link, reminders.notes, reminders.notify_before_minutes,
+    reminders.notified_at IS NOT NULL, reminders.done, jobs.title, jobs.company, jobs.apply_url, reminders.sequence,
+    reminders.updated_at";
+
+#[tauri::command]
+pub fn list_reminders(app_handle: AppHandle, job_id: Option<i32>, include_done: bool) -> Result<Vec<JobReminder>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM reminders JOIN jobs ON jobs.id = reminders.job_id
+         WHERE (?1 IS NULL OR reminders.job_id = ?1) AND (?2 OR reminders.done = 0)
+         ORDER BY reminders.due_at, reminders.id",
+        REMINDER_COLUMNS
+    )).map_err(|e| e.to_string())?;
+
+    let reminders = stmt.query_map(params![job_id, include_done], reminder_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(reminders)
+}
+
+#[tauri::command]
+pub fn add_reminder(app_handle: AppHandle, job_id: i32, reminder: JobReminder) -> Result<JobReminder, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let job_exists: bool = conn.query_row("SELECT COUNT(*) > 0 FROM jobs WHERE id = ?1", [job_id], |row| row.get(0))
+        .map_err(|e| e.to_string())?;
+    if !job_exists {
+        return Err(format!("Job {} not found", job_id));
+    }
+
+    let due_at = validate_reminder(&conn, &reminder)?;
+    conn.execute(
+        "INSERT INTO reminders (job_id, kind, title, due_at, duration_minutes, location, link, notes, notify_before_minutes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            job_id,
+            reminder.kind,
+            reminder_title(&reminder),
+            due_at,
+            reminder.duration_minutes,
+            reminder.location.trim(),
+            reminder.link.trim(),
+            reminder.notes.trim(),
+            reminder.notify_before_minutes,
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_reminder(&conn, conn.last_insert_rowid() as i32)
+}
+
+#[tauri::command]
+pub fn update_reminder(app_handle: AppHandle, reminder_id: i32, reminder: JobReminder) -> Result<JobReminder, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let due_at = validate_reminder(&conn, &reminder)?;
+
+    // Moving the reminder means it has to fire again at the new time
+    conn.execute(
+        "UPDATE reminders SET kind = ?1, title = ?2, location = ?3, link = ?4, notes = ?5, duration_minutes = ?6,
+            notified_at = CASE WHEN due_at = ?7 AND notify_before_minutes = ?8 THEN notified_at ELSE NULL END,
+            due_at = ?7, notify_before_minutes = ?8, sequence = sequence + 1, updated_at = datetime('now')
+         WHERE id = ?9",
+        params![
+            reminder.kind,
+            reminder_title(&reminder),
+            reminder.location.trim(),
+            reminder.link.trim(),
+            reminder.notes.trim(),
+            reminder.duration_minutes,
+            due_at,
+            reminder.notify_before_minutes,
+            reminder_id,
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_reminder(&conn, reminder_id)
+}
+
+#[tauri::command]
+pub fn set_reminder_done(app_handle: AppHandle, reminder_id: i32, done: bool) -> Result<JobReminder, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE reminders SET done = ?1, sequence = sequence + 1, updated_at = datetime('now') WHERE id = ?2",
+        params![done, reminder_id],
+    ).map_err(|e| e.to_string())?;
+
+    get_reminder(&conn, reminder_id)
+}
+
+#[tauri::command]
+pub fn delete_reminder(app_handle: AppHandle, reminder_id: i32) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM reminders WHERE id = ?1", [reminder_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn export_reminders(app_handle: AppHandle, path: String) -> Result<usize, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    write_reminders(
+        &conn,
+        "reminders.done = 0 AND reminders.due_at >= strftime('%Y-%m-%d %H:%M', 'now', 'localtime')",
+        &path,
+    )
+}
+
+#[tauri::command]
+pub fn export_calendar(app_handle: AppHandle, path: String) -> Result<usize, String> {
+    // Everything, past and done included, so re-importing the file brings an existing calendar fully up to date
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    write_reminders(&conn, "1 = 1", &path)
+}
+
+fn write_reminders(conn: &Connection, condition: &str, path: &str) -> Result<usize, String> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM reminders JOIN jobs ON jobs.id = reminders.job_id WHERE {} ORDER BY reminders.due_at",
+        REMINDER_COLUMNS, condition
+    )).map_err(|e| e.to_string())?;
+    let reminders = stmt.query_map([], reminder_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let events = reminders.iter()
+        .map(|reminder| reminder_event(conn, reminder))
+        .collect::<Result<Vec<_>, _>>()?;
+    fs::write(path, write_calendar(&events, &calendar_stamp(conn)?)).map_err(|e| e.to_string())?;
+
+    info!("Exported {} calendar events to {}", events.len(), path);
+    Ok(events.len())
+}
+
+pub fn notify_due_reminders(app_handle: &AppHandle) {
+    let due = match due_reminders(app_handle) {
+        Ok(due) => due,
+        Err(e) => {
+            error!("Failed to load due reminders: {}", e);
+            return;
+        }
+    };
+    // Left pending during quiet hours so they still fire once notifications are allowed again
+    if due.is_empty() || !notifications_allowed(app_handle) {
+        return;
+    }
+
+    let conn = match Connection::open(get_db_path(app_handle)) {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Failed to open database: {}", e);
+            return;
+        }
+    };
+
+    for reminder in due {
+        let mut body = format!("{} at {} - {}", reminder.job_title, reminder.company, reminder.due_at);
+        if !reminder.location.is_empty() {
+            body.push_str(&format!("\n{}", reminder.location));
+        }
+        if !reminder.link.is_empty() {
+            body.push_str(&format!("\n{}", reminder.link));
+        }
+
+        if show_notification(app_handle, &reminder.title, &body) {
+            if let Err(e) = conn.execute("UPDATE reminders SET notified_at = datetime('now') WHERE id = ?1", [reminder.id]) {
+                error!("Failed to mark reminder {} as notified: {}", reminder.id, e);
+            }
+            let _ = app_handle.emit_all("reminder-due", &reminder);
+        }
+    }
+}
+
+fn due_reminders(app_handle: &AppHandle) -> Result<Vec<JobReminder>, String> {
+    // Anything more than a day overdue was missed while the app was closed and is no longer worth a popup
+    let conn = Connection::open(get_db_path(app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM reminders JOIN jobs ON jobs.id = reminders.job_id
+         WHERE reminders.done = 0 AND reminders.notified_at IS NULL
+           AND datetime(reminders.due_at, printf('-%d minutes', reminders.notify_before_minutes)) <= datetime('now', 'localtime')
+           AND reminders.due_at >= datetime('now', 'localtime', '-1 day')
+         ORDER BY reminders.due_at",
+        REMINDER_COLUMNS
+    )).map_err(|e| e.to_string())?;
+
+    let reminders = stmt.query_map([], reminder_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(reminders)
+}
+
+fn reminder_event(conn: &Connection, reminder: &JobReminder) -> Result<CalendarEvent, String> {
+    // A deadline with no time of day is a whole-day event, which calendars show as a banner rather than a slot
+    let all_day = reminder.kind == "deadline" && reminder.due_at.ends_with("00:00");
+    let ends_at: Option<String> = if all_day {
+        Some(conn.query_row("SELECT date(?1, '+1 day')", [&reminder.due_at], |row| row.get(0))
+            .map_err(|e| e.to_string())?)
+    } else if reminder.duration_minutes > 0 {
+        Some(conn.query_row(
+            "SELECT strftime('%Y-%m-%d %H:%M', ?1, printf('+%d minutes', ?2))",
+            params![reminder.due_at, reminder.duration_minutes],
+            |row| row.get(0),
+        ).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+
+    let mut description = format!("{} at {}", reminder.job_title, reminder.company);
+    if !reminder.notes.is_empty() {
+        description.push_str(&format!("\n\n{}", reminder.notes));
+    }
+
+    let categories = match reminder.kind.as_str() {
+        "interview" => "Interview",
+        "deadline" => "Deadline",
+        _ => "Follow-up",
+    };
+
+    Ok(CalendarEvent {
+        uid: format!("reminder-{}@solicit", reminder.id),
+        summary: format!("{}: {} at {}", reminder.title, reminder.job_title, reminder.company),
+        description,
+        location: reminder.location.clone(),
+        url: if reminder.link.is_empty() { reminder.apply_url.clone() } else { reminder.link.clone() },
+        categories: categories.to_string(),
+        starts_at: reminder.due_at.clone(),
+        ends_at,
+        all_day,
+        alarm_minutes: Some(reminder.notify_before_minutes).filter(|minutes| *minutes > 0),
+        sequence: reminder.sequence,
+        last_modified: Some(reminder.updated_at.clone()),
+        cancelled: reminder.done,
+    })
+}
+
+fn calendar_stamp(conn: &Connection) -> Result<String, String> {
+    conn.query_row("SELECT strftime('%Y%m%dT%H%M%SZ', 'now')", [], |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+fn get_reminder(conn: &Connection, reminder_id: i32) -> Result<JobReminder, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM reminders JOIN jobs ON jobs.id = reminders.job_id WHERE reminders.id = ?1", REMINDER_COLUMNS),
+        [reminder_id],
+        reminder_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+fn reminder_from_row(row: &rusqlite::Row) -> rusqlite::Result<JobReminder> {
+    Ok(JobReminder {
+        id: row.get(0)?,
+        job_id: row.get(1)?,
+        kind: row.get(2)?,
+        title: row.get(3)?,
+        due_at: row.get(4)?,
+        duration_minutes: row.get(5)?,
+        location: row.get(6)?,
+        link: row.get(7)?,
+        notes: row.get(8)?,
+        notify_before_minutes: row.get(9)?,
+        notified: row.get(10)?,
+        done: row.get(11)?,
+        job_title: row.get(12)?,
+        company: row.get(13)?,
+        apply_url: row.get::<_, Option<String>>(14)?.unwrap_or_default(),
+        sequence: row.get(15)?,
+        updated_at: row.get(16)?,
+    })
+}
+
+fn validate_reminder(conn: &Connection, reminder: &JobReminder) -> Result<String, String> {
+    if !REMINDER_KINDS.contains(&reminder.kind.as_str()) {
+        return Err(format!("Unknown reminder kind: {}", reminder.kind));
+    }
+    if reminder.duration_minutes < 0 || reminder.notify_before_minutes < 0 {
+        return Err("Durations can't be negative".to_string());
+    }
+
+    // SQLite parses "2024-05-01 14:30", "2024-05-01T14:30" and plain dates, and returns NULL for anything else
+    let due_at: Option<String> = conn.query_row(
+        "SELECT strftime('%Y-%m-%d %H:%M', ?1)",
+        [reminder.due_at.trim()],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+
+    due_at.ok_or_else(|| format!("Not a valid date and time: {}", reminder.due_at))
+}
+
+fn reminder_title(reminder: &JobReminder) -> String {
+    let title = reminder.title.trim();
+    if !title.is_empty() {
+        return title.to_string();
+    }
+
+    match reminder.kind.as_str() {
+        "interview" => "Interview",
+        "deadline" => "Application deadline",
+        _ => "Follow up",
+    }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reminder() -> JobReminder {
+        JobReminder {
+            id: 12,
+            job_id: 3,
+            kind: "interview".to_string(),
+            title: "Interview".to_string(),
+            due_at: "2024-05-01 14:30".to_string(),
+            duration_minutes: 60,
+            job_title: "Rust Developer".to_string(),
+            company: "Ferris".to_string(),
+            sequence: 0,
+            updated_at: "2024-04-20 09:15:00".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn uid_stays_the_same_across_edits() {
+        let conn = Connection::open_in_memory().unwrap();
+        let original = reminder_event(&conn, &reminder()).unwrap();
+
+        let moved = JobReminder {
+            title: "Second interview".to_string(),
+            due_at: "2024-05-03 10:00".to_string(),
+            sequence: 1,
+            ..reminder()
+        };
+        let moved = reminder_event(&conn, &moved).unwrap();
+        let done = reminder_event(&conn, &JobReminder { done: true, sequence: 2, ..reminder() }).unwrap();
+
+        assert_eq!(original.uid, "reminder-12@solicit");
+        assert_eq!(moved.uid, original.uid);
+        assert_eq!(done.uid, original.uid);
+        assert!(moved.sequence > original.sequence && done.sequence > moved.sequence);
+        assert_eq!(moved.ends_at.as_deref(), Some("2024-05-03 11:00"));
+    }
+
+    #[test]
+    fn done_reminders_are_cancelled() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert!(!reminder_event(&conn, &reminder()).unwrap().cancelled);
+        assert!(reminder_event(&conn, &JobReminder { done: true, ..reminder() }).unwrap().cancelled);
+    }
+
+    #[test]
+    fn midnight_deadlines_are_all_day() {
+        let conn = Connection::open_in_memory().unwrap();
+        let deadline = JobReminder { kind: "deadline".to_string(), due_at: "2024-05-10 00:00".to_string(), ..reminder() };
+        let event = reminder_event(&conn, &deadline).unwrap();
+        assert!(event.all_day);
+        assert_eq!(event.ends_at.as_deref(), Some("2024-05-11"));
+    }
+}
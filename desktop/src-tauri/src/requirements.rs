@@ -0,0 +1,280 @@
+use crate::schemas::JobRequirements;
+use crate::helpers::get_db_path;
+use crate::llm::ask_for_json;
+
+use regex::Regex;
+use rusqlite::{params, Connection};
+use serde_json::Value;
+use log::{info, error};
+use tauri::AppHandle;
+
+const NICE_HEADINGS: &[&str] = &["nice to have", "nice-to-have", "bonus", "preferred", "desirable", "a plus"];
+const MUST_HEADINGS: &[&str] = &[
+    "requirement", "must have", "must-have", "qualifications", "what you'll need", "what you need", "you have",
+    "you bring", "looking for", "skills",
+];
+
+// Checked in order, so "senior" wins over "lead" in "Senior Tech Lead"
+const SENIORITY_LEVELS: &[(&str, &str)] = &[
+    ("principal", "principal"), ("staff", "staff"), ("senior", "senior"), ("sr", "senior"), ("lead", "lead"),
+    ("mid", "mid"), ("intermediate", "mid"), ("junior", "junior"), ("jr", "junior"), ("entry level", "junior"),
+    ("graduate", "junior"), ("intern", "intern"), ("internship", "intern"),
+];
+
+#[tauri::command]
+pub async fn extract_requirements(app_handle: AppHandle, description: String, job_id: Option<i32>) -> Result<JobRequirements, String> {
+    if description.trim().is_empty() {
+        return Err("There is no job description to extract requirements from".to_string());
+    }
+
+    let requirements = match llm_requirements(&app_handle, &description).await {
+        Ok(requirements) => requirements,
+        Err(e) => {
+            error!("LLM requirement extraction failed, reading the description locally: {}", e);
+            parse_requirements(&description)
+        }
+    };
+
+    if let Some(job_id) = job_id {
+        let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+        conn.execute(
+            "UPDATE jobs SET extracted_requirements = ?1 WHERE id = ?2",
+            params![serde_json::to_string(&requirements).map_err(|e| e.to_string())?, job_id],
+        ).map_err(|e| e.to_string())?;
+        info!("Stored extracted requirements for job {}", job_id);
+    }
+
+    Ok(requirements)
+}
+
+impl JobRequirements {
+    pub fn to_key_requirements(&self) -> String {
+        // Same shape users tend to type into the keyRequirements box by hand
+        let mut lines = Vec::new();
+
+        if !self.must_have.is_empty() {
+            lines.push(format!("Must have: {}", self.must_have.join(", ")));
+        }
+        if !self.nice_to_have.is_empty() {
+            lines.push(format!("Nice to have: {}", self.nice_to_have.join(", ")));
+        }
+        if !self.seniority.is_empty() {
+            lines.push(format!("Seniority: {}", self.seniority));
+        }
+        if let Some(years) = self.years_experience {
+            lines.push(format!("Experience: {}+ years", years));
+        }
+        if !self.remote_policy.is_empty() {
+            lines.push(format!("Remote policy: {}", self.remote_policy));
+        }
+        if !self.salary_hints.is_empty() {
+            lines.push(format!("Salary: {}", self.salary_hints.join("; ")));
+        }
+
+        lines.join("\n")
+    }
+}
+
+async fn llm_requirements(app_handle: &AppHandle, description: &str) -> Result<JobRequirements, String> {
+    let prompt = format!(
+        "Extract the key requirements from this job description. Reply with only a JSON object with the keys \
+         \"must_have\" and \"nice_to_have\" (arrays of short skill or technology names, not sentences), \
+         \"seniority\" (intern, junior, mid, senior, lead, staff, principal or empty), \
+         \"years_experience\" (number or null), \"remote_policy\" (remote, hybrid, onsite or empty) and \
+         \"salary_hints\" (array of salary text quoted from the description).\n\n{}",
+        description
+    );
+    let reply = ask_for_json(app_handle, &prompt).await?;
+    Ok(parse_llm_requirements(&reply))
+}
+
+pub fn parse_llm_requirements(reply: &Value) -> JobRequirements {
+    // Tolerate the model wrapping its answer or drifting between snake and camel case
+    let value = if reply["requirements"].is_object() {
+        &reply["requirements"]
+    } else {
+        reply
+    };
+
+    JobRequirements {
+        must_have: strings(value, &["must_have", "mustHave"]),
+        nice_to_have: strings(value, &["nice_to_have", "niceToHave"]),
+        seniority: string(value, &["seniority"]).to_lowercase(),
+        years_experience: [&value["years_experience"], &value["yearsExperience"]].iter()
+            .find_map(|years| match years {
+                Value::Number(n) => n.as_u64().or_else(|| n.as_f64().map(|f| f as u64)),
+                Value::String(s) => s.chars()
+                    .skip_while(|c| !c.is_ascii_digit())
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse()
+                    .ok(),
+                _ => None,
+            })
+            .map(|years| years as u32),
+        remote_policy: string(value, &["remote_policy", "remotePolicy", "remote"]).to_lowercase(),
+        salary_hints: strings(value, &["salary_hints", "salaryHints", "salary"]),
+    }
+}
+
+fn string(value: &Value, keys: &[&str]) -> String {
+    keys.iter()
+        .find_map(|key| value[*key].as_str())
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+fn strings(value: &Value, keys: &[&str]) -> Vec<String> {
+    match keys.iter().map(|key| &value[*key]).find(|v| !v.is_null()) {
+        Some(Value::Array(items)) => items.iter()
+            .filter_map(|item| item.as_str())
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        Some(Value::String(s)) if !s.trim().is_empty() => vec![s.trim().to_string()],
+        _ => Vec::new(),
+    }
+}
+
+pub fn parse_requirements(description: &str) -> JobRequirements {
+    // Fallback for when the LLM is unavailable. Works on html_to_text output, where boards' bullet
+    // lists become "- " lines under a heading
+    let mut requirements = JobRequirements::default();
+    let mut section: Option<bool> = None;
+
+    for line in description.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let bullet = ["- ", "* ", "• "].iter().find_map(|prefix| line.strip_prefix(prefix));
+        match bullet {
+            Some(item) => match section {
+                Some(true) => requirements.must_have.push(item.trim().to_string()),
+                Some(false) => requirements.nice_to_have.push(item.trim().to_string()),
+                None => {}
+            },
+            // Short lines are headings and start a new section, longer prose leaves the current one alone
+            None if line.split_whitespace().count() <= 8 => section = heading_section(line),
+            None => {}
+        }
+
+        if bullet.is_none() && is_salary_hint(line) && line.len() <= 200 {
+            requirements.salary_hints.push(line.to_string());
+        }
+    }
+
+    let lower = description.to_lowercase();
+    requirements.seniority = SENIORITY_LEVELS.iter()
+        .find(|(word, _)| contains_word(&lower, word))
+        .map(|(_, level)| level.to_string())
+        .unwrap_or_default();
+    requirements.years_experience = Regex::new(r"(\d{1,2})\s*\+?\s*(?:(?:-|–|to)\s*\d{1,2}\s*)?\+?\s*years?")
+        .ok()
+        .and_then(|years| years.captures(&lower))
+        .and_then(|captures| captures[1].parse().ok());
+    requirements.remote_policy = if contains_word(&lower, "hybrid") {
+        "hybrid"
+    } else if contains_word(&lower, "remote") || lower.contains("work from home") {
+        "remote"
+    } else if ["on-site", "onsite", "on site", "in the office", "in-office"].iter().any(|term| lower.contains(term)) {
+        "onsite"
+    } else {
+        ""
+    }.to_string();
+
+    requirements
+}
+
+fn heading_section(line: &str) -> Option<bool> {
+    // Nice to have is checked first so "Preferred qualifications" doesn't count as a must
+    let lower = line.to_lowercase();
+    if NICE_HEADINGS.iter().any(|heading| lower.contains(heading)) {
+        Some(false)
+    } else if MUST_HEADINGS.iter().any(|heading| lower.contains(heading)) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+fn is_salary_hint(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    let has_amount = line.chars().zip(line.chars().skip(1))
+        .any(|(symbol, digit)| matches!(symbol, '$' | '£' | '€') && digit.is_ascii_digit());
+    has_amount || (["salary", "compensation", "per annum"].iter().any(|term| lower.contains(term))
+        && line.chars().any(|c| c.is_ascii_digit()))
+}
+
+fn contains_word(text: &str, word: &str) -> bool {
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.map(char::is_alphanumeric).unwrap_or(false) && !after.map(char::is_alphanumeric).unwrap_or(false)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = "Senior Backend Engineer
+We are a small team building payment infrastructure and this line is long enough to be prose.
+Requirements:
+- 5+ years of Rust or Go
+- Experience with PostgreSQL
+Nice to have:
+- Kubernetes
+Benefits
+- Free lunch
+This is a hybrid role, two days a week in London.
+Salary: £70,000 - £85,000 per year";
+
+    #[test]
+    fn bullets_follow_their_heading() {
+        let requirements = parse_requirements(DESCRIPTION);
+        assert_eq!(requirements.must_have, vec!["5+ years of Rust or Go", "Experience with PostgreSQL"]);
+        assert_eq!(requirements.nice_to_have, vec!["Kubernetes"]);
+    }
+
+    #[test]
+    fn seniority_years_remote_and_salary_are_found() {
+        let requirements = parse_requirements(DESCRIPTION);
+        assert_eq!(requirements.seniority, "senior");
+        assert_eq!(requirements.years_experience, Some(5));
+        assert_eq!(requirements.remote_policy, "hybrid");
+        assert_eq!(requirements.salary_hints, vec!["Salary: £70,000 - £85,000 per year"]);
+    }
+
+    #[test]
+    fn preferred_qualifications_are_nice_to_have() {
+        let requirements = parse_requirements("Preferred qualifications\n- Terraform\nMinimum qualifications\n- Python");
+        assert_eq!(requirements.nice_to_have, vec!["Terraform"]);
+        assert_eq!(requirements.must_have, vec!["Python"]);
+    }
+
+    #[test]
+    fn llm_replies_are_read_in_either_case() {
+        let requirements = parse_llm_requirements(&serde_json::json!({
+            "requirements": {
+                "mustHave": ["Rust", " PostgreSQL "],
+                "nice_to_have": "Kubernetes",
+                "seniority": "Senior",
+                "yearsExperience": "5+ years",
+                "remote_policy": "Hybrid",
+                "salary_hints": []
+            }
+        }));
+        assert_eq!(requirements.must_have, vec!["Rust", "PostgreSQL"]);
+        assert_eq!(requirements.nice_to_have, vec!["Kubernetes"]);
+        assert_eq!(requirements.seniority, "senior");
+        assert_eq!(requirements.years_experience, Some(5));
+        assert_eq!(requirements.remote_policy, "hybrid");
+        assert!(requirements.salary_hints.is_empty());
+    }
+
+    #[test]
+    fn words_inside_other_words_are_ignored() {
+        let requirements = parse_requirements("Help us build internal tooling for a midsize remote-first company");
+        assert_eq!(requirements.seniority, "");
+        assert_eq!(requirements.remote_policy, "remote");
+        assert_eq!(requirements.years_experience, None);
+    }
+}
@@ -0,0 +1,334 @@
+use crate::schemas::{CvDetails, ExperienceEntry, EducationEntry, Project, Certificate, Skill, ParsedDetails};
+use crate::cv::{read_cv_details, save_cv_details};
+use crate::appconfig::{read_applicant_details, write_applicant_details};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use log::{info, error};
+use tauri::AppHandle;
+use std::fs;
+
+// Subset of the JSON Resume schema (https://jsonresume.org/schema) that maps onto applicant details.
+// Sections outside it (basics.location, profiles, volunteer, awards, ...) and interest keywords are
+// dropped on import, everything that is kept survives an export and re-import unchanged.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JsonResume {
+    pub basics: ResumeBasics,
+    pub work: Vec<ResumeWork>,
+    pub projects: Vec<ResumeProject>,
+    pub education: Vec<ResumeEducation>,
+    pub certificates: Vec<ResumeCertificate>,
+    pub skills: Vec<ResumeSkill>,
+    pub interests: Vec<ResumeInterest>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumeBasics {
+    pub name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub label: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub email: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub phone: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub url: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResumeWork {
+    #[serde(alias = "company")]
+    pub name: String,
+    pub position: String,
+    pub start_date: String,
+    pub end_date: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub summary: String,
+    pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumeProject {
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ResumeEducation {
+    pub institution: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub area: String,
+    pub study_type: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub start_date: String,
+    pub end_date: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumeCertificate {
+    pub name: String,
+    pub issuer: String,
+    pub date: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumeSkill {
+    pub name: String,
+    pub level: String,
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResumeInterest {
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub keywords: Vec<String>,
+}
+
+#[tauri::command]
+pub fn import_profile(app_handle: AppHandle, path: String) -> Result<CvDetails, String> {
+    let contents = fs::read_to_string(&path).map_err(|e| {
+        error!("Failed to read JSON Resume {}: {}", path, e);
+        e.to_string()
+    })?;
+
+    let resume: JsonResume = serde_json::from_str(&contents)
+        .map_err(|e| format!("Not a valid JSON Resume file: {}", e))?;
+
+    let details = CvDetails::from(resume);
+    save_cv_details(&app_handle, &details)?;
+
+    let flat = ParsedDetails::from(&details);
+    let applicant_details = json!({
+        "name": details.name,
+        "experience": flat.experience,
+        "interests": flat.interests,
+        "projects": flat.projects,
+        "education": flat.education,
+        "certificates": flat.certificates
+    });
+    write_applicant_details(app_handle, applicant_details.to_string())?;
+
+    info!("Imported profile from {}", path);
+    Ok(details)
+}
+
+#[tauri::command]
+pub fn export_profile(app_handle: AppHandle, path: String) -> Result<(), String> {
+    let mut details = read_cv_details(app_handle.clone())?;
+
+    if details.name.is_empty() {
+        // The name is edited on the flat applicant details rather than the parsed CV
+        let applicant_details: Value = read_applicant_details(app_handle)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        details.name = applicant_details["name"].as_str().unwrap_or_default().to_string();
+    }
+
+    let content = serde_json::to_string_pretty(&JsonResume::from(details))
+        .map_err(|e| e.to_string())?;
+
+    fs::write(&path, content).map_err(|e| {
+        error!("Failed to write JSON Resume {}: {}", path, e);
+        e.to_string()
+    })?;
+
+    info!("Exported profile to {}", path);
+    Ok(())
+}
+
+impl From<JsonResume> for CvDetails {
+    fn from(resume: JsonResume) -> Self {
+        CvDetails {
+            name: resume.basics.name,
+            label: resume.basics.label,
+            email: resume.basics.email,
+            phone: resume.basics.phone,
+            url: resume.basics.url,
+            summary: resume.basics.summary,
+            experience: resume.work.into_iter().map(|work| ExperienceEntry {
+                employer: work.name,
+                role: work.position,
+                start_date: work.start_date,
+                end_date: work.end_date,
+                summary: work.summary,
+                bullets: work.highlights,
+            }).collect(),
+            interests: resume.interests.into_iter().map(|interest| interest.name).collect(),
+            projects: resume.projects.into_iter().map(|project| Project {
+                name: project.name,
+                description: project.description,
+                url: project.url,
+                highlights: project.highlights,
+            }).collect(),
+            education: resume.education.into_iter().map(|education| EducationEntry {
+                degree: education.study_type,
+                area: education.area,
+                institution: education.institution,
+                start_date: education.start_date,
+                year: education.end_date,
+            }).collect(),
+            certificates: resume.certificates.into_iter().map(|certificate| Certificate {
+                name: certificate.name,
+                issuer: certificate.issuer,
+                date: certificate.date,
+            }).collect(),
+            skills: resume.skills.into_iter().map(|skill| Skill {
+                name: skill.name,
+                level: skill.level,
+                keywords: skill.keywords,
+            }).collect(),
+        }
+    }
+}
+
+impl From<CvDetails> for JsonResume {
+    fn from(details: CvDetails) -> Self {
+        JsonResume {
+            basics: ResumeBasics {
+                name: details.name,
+                label: details.label,
+                email: details.email,
+                phone: details.phone,
+                url: details.url,
+                summary: details.summary,
+            },
+            work: details.experience.into_iter().map(|entry| ResumeWork {
+                name: entry.employer,
+                position: entry.role,
+                start_date: entry.start_date,
+                end_date: entry.end_date,
+                summary: entry.summary,
+                highlights: entry.bullets,
+            }).collect(),
+            projects: details.projects.into_iter().map(|project| ResumeProject {
+                name: project.name,
+                description: project.description,
+                url: project.url,
+                highlights: project.highlights,
+            }).collect(),
+            education: details.education.into_iter().map(|entry| ResumeEducation {
+                institution: entry.institution,
+                area: entry.area,
+                study_type: entry.degree,
+                start_date: entry.start_date,
+                end_date: entry.year,
+            }).collect(),
+            certificates: details.certificates.into_iter().map(|certificate| ResumeCertificate {
+                name: certificate.name,
+                issuer: certificate.issuer,
+                date: certificate.date,
+            }).collect(),
+            skills: details.skills.into_iter().map(|skill| ResumeSkill {
+                name: skill.name,
+                level: skill.level,
+                keywords: skill.keywords,
+            }).collect(),
+            interests: details.interests.into_iter().map(|name| ResumeInterest {
+                name,
+                keywords: Vec::new(),
+            }).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_details() -> CvDetails {
+        CvDetails {
+            name: "Ada Lovelace".to_string(),
+            label: "Software Engineer".to_string(),
+            email: "ada@example.com".to_string(),
+            phone: "+44 20 7946 0000".to_string(),
+            url: "https://ada.example.com".to_string(),
+            summary: "Backend developer who likes compilers.".to_string(),
+            experience: vec![ExperienceEntry {
+                employer: "Analytical Engines Ltd".to_string(),
+                role: "Senior Engineer".to_string(),
+                start_date: "2019-03".to_string(),
+                end_date: "2023-08".to_string(),
+                summary: "Led the platform team.".to_string(),
+                bullets: vec!["Cut build times by 40%".to_string(), "Mentored four engineers".to_string()],
+            }],
+            interests: vec!["Chess".to_string(), "Climbing".to_string()],
+            projects: vec![Project {
+                name: "notes".to_string(),
+                description: "Markdown note taker".to_string(),
+                url: "https://github.com/ada/notes".to_string(),
+                highlights: vec!["Rust".to_string(), "SQLite".to_string()],
+            }],
+            education: vec![EducationEntry {
+                degree: "BSc".to_string(),
+                area: "Mathematics".to_string(),
+                institution: "University of London".to_string(),
+                start_date: "2012".to_string(),
+                year: "2015".to_string(),
+            }],
+            certificates: vec![Certificate {
+                name: "AWS Solutions Architect".to_string(),
+                issuer: "Amazon".to_string(),
+                date: "2021-05".to_string(),
+            }],
+            skills: vec![Skill {
+                name: "Rust".to_string(),
+                level: "Advanced".to_string(),
+                keywords: vec!["tokio".to_string(), "serde".to_string()],
+            }],
+        }
+    }
+
+    #[test]
+    fn export_then_import_keeps_every_field() {
+        let details = sample_details();
+        let json = serde_json::to_string(&JsonResume::from(details.clone())).unwrap();
+        let imported = CvDetails::from(serde_json::from_str::<JsonResume>(&json).unwrap());
+        assert_eq!(imported, details);
+    }
+
+    #[test]
+    fn empty_profile_round_trips() {
+        let details = CvDetails::default();
+        let json = serde_json::to_string(&JsonResume::from(details.clone())).unwrap();
+        let imported = CvDetails::from(serde_json::from_str::<JsonResume>(&json).unwrap());
+        assert_eq!(imported, details);
+    }
+
+    #[test]
+    fn import_keeps_education_and_work_summary_apart() {
+        let resume: JsonResume = serde_json::from_str(r#"{
+            "basics": {"name": "Ada", "email": "ada@example.com", "location": {"city": "London"}},
+            "work": [{"company": "Acme", "position": "Dev", "summary": "Built things", "highlights": ["Shipped"]}],
+            "education": [{"institution": "UCL", "studyType": "MSc", "area": "Computer Science", "startDate": "2015", "endDate": "2016"}]
+        }"#).unwrap();
+        let details = CvDetails::from(resume);
+
+        assert_eq!(details.email, "ada@example.com");
+        assert_eq!(details.experience[0].employer, "Acme");
+        assert_eq!(details.experience[0].summary, "Built things");
+        assert_eq!(details.experience[0].bullets, vec!["Shipped".to_string()]);
+        assert_eq!(details.education[0].degree, "MSc");
+        assert_eq!(details.education[0].area, "Computer Science");
+        assert_eq!(details.education[0].start_date, "2015");
+        assert_eq!(details.education[0].year, "2016");
+
+        let exported = serde_json::to_value(JsonResume::from(details)).unwrap();
+        assert_eq!(exported["education"][0]["studyType"], "MSc");
+        assert_eq!(exported["education"][0]["area"], "Computer Science");
+        assert_eq!(exported["work"][0]["summary"], "Built things");
+    }
+}
@@ -0,0 +1,284 @@
+use crate::schemas::{Job, JobRule};
+use crate::server::{job_from_row, record_status, JOB_COLUMNS};
+use crate::jobnotes::add_tags;
+use crate::helpers::get_db_path;
+
+use regex::{Regex, RegexBuilder};
+use rusqlite::{params, Connection};
+use tauri::AppHandle;
+use log::info;
+
+const RULE_FIELDS: &[&str] = &["title", "company", "location", "description"];
+const RULE_KINDS: &[&str] = &["contains", "regex", "salary_floor"];
+const RULE_ACTIONS: &[&str] = &["hide", "tag", "mark_read"];
+
+const RULE_COLUMNS: &str = "id, name, field, kind, pattern, min_salary, mode, action, tag, enabled";
+
+struct CompiledRule {
+    rule: JobRule,
+    regex: Option<Regex>,
+}
+
+#[tauri::command]
+pub fn list_rules(app_handle: AppHandle) -> Result<Vec<JobRule>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    load_rules(&conn, false).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn add_rule(app_handle: AppHandle, rule: JobRule) -> Result<JobRule, String> {
+    compile_rule(&rule)?;
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO job_rules (name, field, kind, pattern, min_salary, mode, action, tag, enabled)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            rule.name.trim(), rule.field, rule.kind, rule.pattern.trim(), rule.min_salary, rule.mode, rule.action,
+            rule.tag.trim(), rule.enabled
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_rule(&conn, conn.last_insert_rowid() as i32)
+}
+
+#[tauri::command]
+pub fn update_rule(app_handle: AppHandle, rule_id: i32, rule: JobRule) -> Result<JobRule, String> {
+    compile_rule(&rule)?;
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE job_rules SET name = ?1, field = ?2, kind = ?3, pattern = ?4, min_salary = ?5, mode = ?6, action = ?7,
+            tag = ?8, enabled = ?9
+         WHERE id = ?10",
+        params![
+            rule.name.trim(), rule.field, rule.kind, rule.pattern.trim(), rule.min_salary, rule.mode, rule.action,
+            rule.tag.trim(), rule.enabled, rule_id
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_rule(&conn, rule_id)
+}
+
+#[tauri::command]
+pub fn delete_rule(app_handle: AppHandle, rule_id: i32) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM job_rules WHERE id = ?1", [rule_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn preview_rule(app_handle: AppHandle, rule: JobRule) -> Result<Vec<Job>, String> {
+    // Dry run: lists the existing jobs the rule would catch without changing any of them
+    let compiled = compile_rule(&rule)?;
+
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM jobs ORDER BY fetched_date DESC", JOB_COLUMNS))
+        .map_err(|e| e.to_string())?;
+    let jobs = stmt.query_map([], job_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(jobs.into_iter().filter(|job| rule_matches(&compiled, job)).collect())
+}
+
+pub fn apply_rules(conn: &Connection, job_ids: &[i32]) -> rusqlite::Result<()> {
+    // Runs on freshly processed jobs, so salary and location are already parsed
+    let rules: Vec<CompiledRule> = load_rules(conn, true)?
+        .into_iter()
+        .filter_map(|rule| compile_rule(&rule).ok())
+        .collect();
+    if rules.is_empty() {
+        return Ok(());
+    }
+
+    let mut affected = 0;
+    for job_id in job_ids {
+        let job = conn.query_row(&format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS), [job_id], job_from_row)?;
+        let mut matched = false;
+
+        for compiled in rules.iter().filter(|compiled| rule_matches(compiled, &job)) {
+            matched = true;
+            match compiled.rule.action.as_str() {
+                "hide" => {
+                    conn.execute("UPDATE jobs SET hidden = 1 WHERE id = ?1", [job_id])?;
+                }
+                "tag" => add_tags(conn, *job_id, std::slice::from_ref(&compiled.rule.tag))?,
+                _ => {
+                    if conn.execute("UPDATE jobs SET read = 1 WHERE id = ?1 AND read = 0", [job_id])? > 0 {
+                        record_status(conn, *job_id, "read")?;
+                    }
+                }
+            }
+        }
+        if matched {
+            affected += 1;
+        }
+    }
+
+    if affected > 0 {
+        info!("Rules applied to {} new jobs", affected);
+    }
+    Ok(())
+}
+
+fn rule_matches(compiled: &CompiledRule, job: &Job) -> bool {
+    let rule = &compiled.rule;
+    if rule.kind == "salary_floor" {
+        // Jobs that don't state a salary get the benefit of the doubt
+        return match (job.salary_annual, rule.min_salary) {
+            (Some(annual), Some(floor)) => annual < floor,
+            _ => false,
+        };
+    }
+
+    let value = match rule.field.as_str() {
+        "company" => &job.company,
+        "location" => &job.location,
+        "description" => &job.description,
+        _ => &job.title,
+    };
+    let matched = match &compiled.regex {
+        Some(regex) => regex.is_match(value),
+        None => {
+            // "contains" takes a comma separated list and matches any of them
+            let value = value.to_lowercase();
+            rule.pattern.split(',')
+                .map(|term| term.trim().to_lowercase())
+                .any(|term| !term.is_empty() && value.contains(&term))
+        }
+    };
+
+    // Include rules act on everything that doesn't match, e.g. hide any title without "rust"
+    if rule.mode == "include" { !matched } else { matched }
+}
+
+fn compile_rule(rule: &JobRule) -> Result<CompiledRule, String> {
+    if !RULE_FIELDS.contains(&rule.field.as_str()) {
+        return Err(format!("Unknown rule field: {}", rule.field));
+    }
+    if !RULE_KINDS.contains(&rule.kind.as_str()) {
+        return Err(format!("Unknown rule type: {}", rule.kind));
+    }
+    if !RULE_ACTIONS.contains(&rule.action.as_str()) {
+        return Err(format!("Unknown rule action: {}", rule.action));
+    }
+    if rule.mode != "include" && rule.mode != "exclude" {
+        return Err(format!("Rules either include or exclude, got {}", rule.mode));
+    }
+    if rule.action == "tag" && rule.tag.trim().is_empty() {
+        return Err("Tagging rules need a tag".to_string());
+    }
+
+    let regex = match rule.kind.as_str() {
+        "salary_floor" => {
+            if rule.min_salary.map(|floor| floor <= 0.0).unwrap_or(true) {
+                return Err("Salary floor rules need a minimum salary".to_string());
+            }
+            // Inverting would act on every job without a salary, which is never what anyone means
+            if rule.mode == "include" {
+                return Err("Salary floor rules act on jobs below the floor, use exclude".to_string());
+            }
+            None
+        }
+        "regex" if rule.pattern.trim().is_empty() => return Err("The rule needs something to match".to_string()),
+        "regex" => Some(
+            RegexBuilder::new(rule.pattern.trim())
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| format!("Invalid regex: {}", e))?
+        ),
+        _ => {
+            if rule.pattern.trim().is_empty() {
+                return Err("The rule needs something to match".to_string());
+            }
+            None
+        }
+    };
+
+    Ok(CompiledRule { rule: rule.clone(), regex })
+}
+
+fn load_rules(conn: &Connection, enabled_only: bool) -> rusqlite::Result<Vec<JobRule>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM job_rules WHERE ?1 = 0 OR enabled = 1 ORDER BY id",
+        RULE_COLUMNS
+    ))?;
+    let rules = stmt.query_map([enabled_only], rule_from_row)?.collect();
+    rules
+}
+
+fn get_rule(conn: &Connection, rule_id: i32) -> Result<JobRule, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM job_rules WHERE id = ?1", RULE_COLUMNS),
+        [rule_id],
+        rule_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+fn rule_from_row(row: &rusqlite::Row) -> rusqlite::Result<JobRule> {
+    Ok(JobRule {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        field: row.get(2)?,
+        kind: row.get(3)?,
+        pattern: row.get(4)?,
+        min_salary: row.get(5)?,
+        mode: row.get(6)?,
+        action: row.get(7)?,
+        tag: row.get(8)?,
+        enabled: row.get(9)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(title: &str, salary_annual: Option<f64>) -> Job {
+        serde_json::from_value(serde_json::json!({
+            "id": 1, "uniqueid": "1", "title": title, "company": "Acme", "location": "", "salary": "",
+            "jobkey": "", "fetched_date": "", "read": false, "appliedto": false, "source": "manual",
+            "match_score": null, "matched_skills": [], "missing_skills": [], "description": "", "requirements": "",
+            "apply_url": "", "extracted_requirements": null, "salary_min": null, "salary_max": null,
+            "salary_currency": null, "salary_period": null, "salary_annual": salary_annual, "city": null,
+            "region": null, "country": null, "work_mode": null
+        })).unwrap()
+    }
+
+    fn rule(kind: &str, pattern: &str, mode: &str) -> JobRule {
+        JobRule { kind: kind.to_string(), pattern: pattern.to_string(), mode: mode.to_string(), ..Default::default() }
+    }
+
+    #[test]
+    fn empty_patterns_are_rejected() {
+        assert!(compile_rule(&rule("contains", " ", "exclude")).is_err());
+        assert!(compile_rule(&rule("regex", "", "exclude")).is_err());
+        assert!(compile_rule(&rule("regex", "(", "exclude")).is_err());
+        assert!(compile_rule(&rule("regex", "^senior", "exclude")).is_ok());
+    }
+
+    #[test]
+    fn include_rules_act_on_everything_else() {
+        let compiled = compile_rule(&rule("contains", "rust, go", "include")).unwrap();
+        assert!(!rule_matches(&compiled, &job("Rust Developer", None)));
+        assert!(rule_matches(&compiled, &job("Java Developer", None)));
+
+        let compiled = compile_rule(&rule("regex", r"\bsenior\b", "exclude")).unwrap();
+        assert!(rule_matches(&compiled, &job("SENIOR Engineer", None)));
+        assert!(!rule_matches(&compiled, &job("Seniority Analyst", None)));
+    }
+
+    #[test]
+    fn salary_floors_leave_unknown_salaries_alone() {
+        let floor = JobRule { min_salary: Some(40_000.0), ..rule("salary_floor", "", "exclude") };
+        let compiled = compile_rule(&floor).unwrap();
+        assert!(rule_matches(&compiled, &job("Developer", Some(30_000.0))));
+        assert!(!rule_matches(&compiled, &job("Developer", Some(50_000.0))));
+        assert!(!rule_matches(&compiled, &job("Developer", None)));
+
+        assert!(compile_rule(&JobRule { mode: "include".to_string(), ..floor }).is_err());
+    }
+}
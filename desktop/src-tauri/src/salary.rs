@@ -0,0 +1,261 @@
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SalaryInfo {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub currency: Option<String>,
+    pub period: Option<String>,
+    pub annual: Option<f64>,
+}
+
+const CURRENCIES: &[(&str, &str)] = &[
+    ("ca$", "CAD"), ("c$", "CAD"), ("cad", "CAD"),
+    ("a$", "AUD"), ("au$", "AUD"), ("aud", "AUD"),
+    ("us$", "USD"), ("usd", "USD"), ("$", "USD"),
+    ("£", "GBP"), ("gbp", "GBP"),
+    ("€", "EUR"), ("eur", "EUR"),
+    ("chf", "CHF"),
+];
+
+// Whichever period is mentioned first in the text wins
+const PERIODS: &[(&str, &[&str])] = &[
+    ("hour", &["hour", "hourly", "/hr", " hr", "/h", "p/h", "ph", "ora", "orario"]),
+    ("day", &["day", "daily", "giorno", "giornata", "giornaliero"]),
+    ("week", &["week", "weekly", "settimana", "settimanale"]),
+    ("month", &["month", "monthly", "pcm", "mese", "mensile"]),
+    ("year", &["year", "annum", "annual", "annually", "p.a", "anno", "annuo", "annuale", "yr"]),
+];
+
+// Words that may sit between the two ends of a range, besides currency symbols
+const RANGE_SEPARATORS: &[&str] = &["-", "–", "—", "to", "a", "and", "bis"];
+
+pub fn parse_salary(text: &str) -> Option<SalaryInfo> {
+    let lower = text.to_lowercase();
+    let numbers = extract_numbers(&lower);
+    let (first, first_start, first_end) = *numbers.first()?;
+
+    // A second figure only counts when it closes a range, later numbers are hours, bonuses and the like
+    let second = numbers.get(1)
+        .filter(|(_, start, _)| is_range_separator(&lower.chars().skip(first_end).take(start - first_end).collect::<String>()))
+        .map(|(value, _, _)| *value);
+    let before: String = lower.chars().take(first_start).collect();
+
+    let (min, max) = if let Some(second) = second {
+        // "40-50k" means 40k to 50k
+        let first = if first < 1000.0 && second >= 1000.0 && first * 1000.0 <= second { first * 1000.0 } else { first };
+        (Some(first.min(second)), Some(first.max(second)))
+    } else if has_any(&before, &["up to", "upto", "max", "maximum", "fino a", "until"]) {
+        (None, Some(first))
+    } else if has_any(&before, &["from", "starting", "min", "minimum", "da", "a partire"]) {
+        (Some(first), None)
+    } else {
+        (Some(first), Some(first))
+    };
+
+    let currency = CURRENCIES.iter()
+        .find(|(symbol, _)| find_word(&lower, symbol).is_some())
+        .map(|(_, code)| code.to_string());
+
+    let period = PERIODS.iter()
+        .filter_map(|(period, words)| words.iter().filter_map(|word| find_word(&lower, word)).min().map(|pos| (pos, period)))
+        .min_by_key(|(pos, _)| *pos)
+        .map(|(_, period)| period.to_string())
+        .or_else(|| {
+            // Bare figures in the tens of thousands are only ever annual
+            let largest = max.or(min).unwrap_or_default();
+            if largest >= 10_000.0 { Some("year".to_string()) } else { None }
+        });
+
+    let annual = period.as_deref().and_then(|period| {
+        let midpoint = match (min, max) {
+            (Some(min), Some(max)) => (min + max) / 2.0,
+            (Some(value), None) | (None, Some(value)) => value,
+            (None, None) => return None,
+        };
+        Some((midpoint * periods_per_year(period)).round())
+    });
+
+    Some(SalaryInfo { min, max, currency, period, annual })
+}
+
+fn periods_per_year(period: &str) -> f64 {
+    match period {
+        "hour" => 40.0 * 52.0,
+        "day" => 5.0 * 52.0,
+        "week" => 52.0,
+        "month" => 12.0,
+        _ => 1.0,
+    }
+}
+
+fn extract_numbers(text: &str) -> Vec<(f64, usize, usize)> {
+    // Each number comes with the char range it was read from
+    let chars: Vec<char> = text.chars().collect();
+    let mut numbers = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        if !chars[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.' || chars[i] == ','
+            || (chars[i] == '\'' && chars.get(i + 1).map(|c| c.is_ascii_digit()).unwrap_or(false)))
+        {
+            i += 1;
+        }
+        let raw: String = chars[start..i].iter().collect();
+        let raw = raw.trim_end_matches(['.', ',']);
+
+        // Skip percentages and the like, they are bonuses rather than pay
+        let next = chars[i..].iter().find(|c| !c.is_whitespace()).copied();
+        if next == Some('%') {
+            continue;
+        }
+
+        if let Some(mut value) = parse_number(raw) {
+            if chars.get(i).map(|c| *c == 'k').unwrap_or(false)
+                && !chars.get(i + 1).map(|c| c.is_alphabetic()).unwrap_or(false)
+            {
+                value *= 1000.0;
+                i += 1;
+            }
+            numbers.push((value, start, i));
+        }
+    }
+
+    numbers
+}
+
+fn parse_number(raw: &str) -> Option<f64> {
+    // Boards use 40,000.50, 40.000,50 and 40'000 depending on locale
+    let raw = &raw.replace('\'', "");
+    let last_dot = raw.rfind('.');
+    let last_comma = raw.rfind(',');
+
+    let decimal = match (last_dot, last_comma) {
+        (Some(dot), Some(comma)) => Some(if dot > comma { '.' } else { ',' }),
+        (Some(_), None) => single_separator_decimal(raw, '.'),
+        (None, Some(_)) => single_separator_decimal(raw, ','),
+        (None, None) => None,
+    };
+
+    let normalised: String = raw.chars()
+        .filter_map(|c| match c {
+            '0'..='9' => Some(c),
+            c if Some(c) == decimal => Some('.'),
+            _ => None,
+        })
+        .collect();
+
+    normalised.parse().ok()
+}
+
+fn single_separator_decimal(raw: &str, separator: char) -> Option<char> {
+    // One separator followed by exactly three digits is a thousands separator
+    if raw.matches(separator).count() > 1 {
+        return None;
+    }
+    let digits_after = raw.rsplit(separator).next().map(|s| s.len()).unwrap_or(0);
+    if digits_after == 3 { None } else { Some(separator) }
+}
+
+fn is_range_separator(between: &str) -> bool {
+    let mut separators = 0;
+    for word in between.split(|c: char| c.is_whitespace() || matches!(c, '$' | '£' | '€')).filter(|word| !word.is_empty()) {
+        if RANGE_SEPARATORS.contains(&word) {
+            separators += 1;
+        } else if !CURRENCIES.iter().any(|(symbol, _)| symbol.trim_end_matches('$') == word) {
+            return false;
+        }
+    }
+    separators == 1
+}
+
+fn has_any(text: &str, phrases: &[&str]) -> bool {
+    phrases.iter().any(|phrase| find_word(text, phrase).is_some())
+}
+
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    // Match on word boundaries so "ora" does not fire inside "lavorare" or "da" inside "canada"
+    let mut offset = 0;
+    while let Some(pos) = text[offset..].find(word) {
+        let start = offset + pos;
+        let end = start + word.len();
+        let before = text[..start].chars().next_back();
+        let after = text[end..].chars().next();
+        let boundary = |c: Option<char>| c.map(|c| !c.is_alphanumeric()).unwrap_or(true);
+        let word_start = !word.starts_with(|c: char| c.is_alphanumeric()) || boundary(before);
+        let word_end = !word.ends_with(|c: char| c.is_alphanumeric()) || boundary(after);
+        if word_start && word_end {
+            return Some(start);
+        }
+        offset = end;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_take_both_ends() {
+        let salary = parse_salary("£40,000 - £50,000 per annum").unwrap();
+        assert_eq!((salary.min, salary.max), (Some(40_000.0), Some(50_000.0)));
+        assert_eq!(salary.currency.as_deref(), Some("GBP"));
+        assert_eq!(salary.period.as_deref(), Some("year"));
+        assert_eq!(salary.annual, Some(45_000.0));
+    }
+
+    #[test]
+    fn shorthand_ranges_share_the_k() {
+        let salary = parse_salary("$40-50k").unwrap();
+        assert_eq!((salary.min, salary.max), (Some(40_000.0), Some(50_000.0)));
+    }
+
+    #[test]
+    fn hours_per_week_are_not_a_range() {
+        let salary = parse_salary("£12.50 per hour, 37.5 hours per week").unwrap();
+        assert_eq!((salary.min, salary.max), (Some(12.5), Some(12.5)));
+        assert_eq!(salary.period.as_deref(), Some("hour"));
+        assert_eq!(salary.annual, Some(26_000.0));
+    }
+
+    #[test]
+    fn later_bonuses_are_ignored() {
+        let salary = parse_salary("$60 an hour, plus up to $10k signing bonus").unwrap();
+        assert_eq!((salary.min, salary.max), (Some(60.0), Some(60.0)));
+        assert_eq!(salary.annual, Some(124_800.0));
+    }
+
+    #[test]
+    fn currency_words_need_word_boundaries() {
+        let salary = parse_salary("Canada $50,000 a year").unwrap();
+        assert_eq!((salary.min, salary.max), (Some(50_000.0), Some(50_000.0)));
+        assert_eq!(salary.annual, Some(50_000.0));
+    }
+
+    #[test]
+    fn first_period_mentioned_wins() {
+        let salary = parse_salary("€300 per day, paid monthly").unwrap();
+        assert_eq!(salary.period.as_deref(), Some("day"));
+        assert_eq!(salary.currency.as_deref(), Some("EUR"));
+    }
+
+    #[test]
+    fn italian_ranges_and_qualifiers() {
+        let salary = parse_salary("RAL da 30.000 a 40.000 €").unwrap();
+        assert_eq!((salary.min, salary.max), (Some(30_000.0), Some(40_000.0)));
+
+        let salary = parse_salary("a partire da 1.800 € al mese").unwrap();
+        assert_eq!((salary.min, salary.max), (Some(1_800.0), None));
+        assert_eq!(salary.period.as_deref(), Some("month"));
+    }
+
+    #[test]
+    fn no_numbers_no_salary() {
+        assert_eq!(parse_salary("Competitive"), None);
+    }
+}
@@ -13,12 +13,368 @@ pub struct Job {
    pub read: bool,
    pub appliedto: bool,
    pub source: String,
+   pub match_score: Option<f64>,
+   pub matched_skills: Vec<String>,
+   pub missing_skills: Vec<String>,
+   pub description: String,
+   pub requirements: String,
+   pub apply_url: String,
+   pub extracted_requirements: Option<JobRequirements>,
+   pub salary_min: Option<f64>,
+   pub salary_max: Option<f64>,
+   pub salary_currency: Option<String>,
+   pub salary_period: Option<String>,
+   pub salary_annual: Option<f64>,
+   pub city: Option<String>,
+   pub region: Option<String>,
+   pub country: Option<String>,
+   pub work_mode: Option<String>,
+   #[serde(default)]
+   pub tags: Vec<String>,
+   #[serde(default)]
+   pub company_id: Option<i32>,
+   #[serde(default)]
+   pub hidden: bool,
+   #[serde(default)]
+   pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NewJob {
+    pub uniqueid: String,
+    pub title: String,
+    pub company: String,
+    pub location: String,
+    pub salary: String,
+    pub jobkey: String,
+    pub source: String,
+    pub description: String,
+    pub apply_url: String,
+    pub read: bool,
+    pub appliedto: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobNote {
+    pub id: i32,
+    pub job_id: i32,
+    pub body: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Tag {
+    pub id: i32,
+    pub name: String,
+    pub job_count: i32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobContact {
+    pub id: i32,
+    pub job_id: i32,
+    pub name: String,
+    pub email: String,
+    pub role: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Company {
+    pub id: i32,
+    pub name: String,
+    pub website: String,
+    pub notes: String,
+    pub rating: Option<i32>,
+    pub blocked: bool,
+    pub recruiter: bool,
+    pub job_count: i32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BlacklistSettings {
+    pub mark_read: bool,
+    pub block_recruiters: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobRule {
+    pub id: i32,
+    pub name: String,
+    pub field: String,
+    pub kind: String,
+    pub pattern: String,
+    pub min_salary: Option<f64>,
+    pub mode: String,
+    pub action: String,
+    pub tag: String,
+    pub enabled: bool,
+}
+
+impl Default for JobRule {
+    fn default() -> Self {
+        JobRule {
+            id: 0,
+            name: String::new(),
+            field: "title".to_string(),
+            kind: "contains".to_string(),
+            pattern: String::new(),
+            min_salary: None,
+            mode: "exclude".to_string(),
+            action: "hide".to_string(),
+            tag: String::new(),
+            enabled: true,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobReminder {
+    pub id: i32,
+    pub job_id: i32,
+    pub kind: String,
+    pub title: String,
+    pub due_at: String,
+    pub duration_minutes: i32,
+    pub location: String,
+    pub link: String,
+    pub notes: String,
+    pub notify_before_minutes: i32,
+    pub notified: bool,
+    pub done: bool,
+    pub job_title: String,
+    pub company: String,
+    pub apply_url: String,
+    pub sequence: i32,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobStatusChange {
+    pub status: String,
+    pub changed_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobExport {
+    #[serde(flatten)]
+    pub job: Job,
+    #[serde(default)]
+    pub status_history: Vec<JobStatusChange>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct JobImportSummary {
+    pub added: i32,
+    pub merged: i32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobFilter {
+    pub min_salary: Option<f64>,
+    pub currency: Option<String>,
+    pub work_mode: Option<String>,
+    pub country: Option<String>,
+    pub region: Option<String>,
+    pub city: Option<String>,
+    pub applied: Option<bool>,
+    pub hidden: Option<bool>,
+    pub archived: Option<bool>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SavedSearch {
+    pub id: i32,
+    pub name: String,
+    pub keywords: String,
+    pub location: String,
+    pub sources: Vec<String>,
+    pub filters: JobFilter,
+    pub interval_minutes: i32,
+    pub enabled: bool,
+    pub notify: bool,
+    pub last_run: Option<String>,
+    pub last_result: Option<String>,
+    pub last_new_jobs: i32,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AtsBoards {
+    pub greenhouse: Vec<String>,
+    pub lever: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Feed {
+    pub id: i32,
+    pub name: String,
+    pub url: String,
+    pub enabled: bool,
+    pub last_fetched: Option<String>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchReport {
+    pub source: String,
+    pub keywords: String,
+    pub location: String,
+    pub pages_fetched: i32,
+    pub jobs_seen: i32,
+    pub inserted: i32,
+    pub duplicates_skipped: i32,
+    pub errors: Vec<String>,
+    pub duration_ms: i64,
+    pub run_at: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchQuery {
+    pub keywords: String,
+    pub location: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SearchAllReport {
+    pub keywords: String,
+    pub location: String,
+    pub reports: Vec<SearchReport>,
+    pub jobs_seen: i32,
+    pub inserted: i32,
+    pub duplicates_skipped: i32,
+    pub errors: Vec<String>,
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct SearchProgress {
+    pub source: String,
+    pub page: i32,
+    pub total_pages: Option<i32>,
+    pub jobs_found: i32,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrawlPolicy {
+    pub delay_ms: u64,
+    pub max_requests_per_hour: u32,
+    pub max_retries: u32,
+    pub backoff_ms: u64,
+    pub min_run_interval_minutes: u64,
+    pub user_agent: String,
+    pub respect_robots: bool,
+}
+
+impl Default for CrawlPolicy {
+    fn default() -> Self {
+        CrawlPolicy {
+            delay_ms: 2000,
+            max_requests_per_hour: 120,
+            max_retries: 3,
+            backoff_ms: 1000,
+            min_run_interval_minutes: 2,
+            user_agent: "Mozilla/5.0 (compatible; Solicit Jobhunter)".to_string(),
+            respect_robots: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupFile {
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format: String,
+    pub version: u32,
+    pub app_version: String,
+    pub created_at: String,
+    pub includes_secrets: bool,
+    pub files: Vec<BackupFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveSettings {
+    pub enabled: bool,
+    pub after_days: u32,
+}
+
+impl Default for ArchiveSettings {
+    fn default() -> Self {
+        ArchiveSettings {
+            enabled: false,
+            after_days: 30,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub disabled: bool,
+    pub quiet_hours_start: Option<String>,
+    pub quiet_hours_end: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearchRun {
+    pub search_id: i32,
+    pub name: String,
+    pub new_jobs: i32,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct JobUpdate {
     pub read: Option<bool>,
     pub appliedto: Option<bool>,
+    pub hidden: Option<bool>,
+    pub archived: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct JobDetails {
+    pub description: String,
+    pub requirements: String,
+    pub apply_url: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct JobRequirements {
+    pub must_have: Vec<String>,
+    pub nice_to_have: Vec<String>,
+    pub seniority: String,
+    pub years_experience: Option<u32>,
+    pub remote_policy: String,
+    pub salary_hints: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MatchResult {
+    pub score: f64,
+    pub matched_skills: Vec<String>,
+    pub missing_skills: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,4 +390,67 @@ pub struct ParsedDetails {
     pub projects: String,
     pub education: String,
     pub certificates: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExperienceEntry {
+    pub employer: String,
+    pub role: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub summary: String,
+    pub bullets: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EducationEntry {
+    pub degree: String,
+    pub area: String,
+    pub institution: String,
+    pub start_date: String,
+    pub year: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    pub name: String,
+    pub description: String,
+    pub url: String,
+    pub highlights: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Certificate {
+    pub name: String,
+    pub issuer: String,
+    pub date: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Skill {
+    pub name: String,
+    pub level: String,
+    pub keywords: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CvDetails {
+    pub name: String,
+    pub label: String,
+    pub email: String,
+    pub phone: String,
+    pub url: String,
+    pub summary: String,
+    pub experience: Vec<ExperienceEntry>,
+    pub interests: Vec<String>,
+    pub projects: Vec<Project>,
+    pub education: Vec<EducationEntry>,
+    pub certificates: Vec<Certificate>,
+    pub skills: Vec<Skill>,
+}
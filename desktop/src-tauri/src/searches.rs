@@ -0,0 +1,305 @@
+use crate::schemas::{SavedSearch, SavedSearchRun, SearchQuery, JobFilter};
+use crate::jobsearch::search_sources;
+use crate::server::filter_clause;
+use crate::companies::blacklist_clause;
+use crate::notifications::notify_new_jobs;
+use crate::reminders::notify_due_reminders;
+use crate::archive::archive_stale;
+use crate::helpers::get_db_path;
+
+use rusqlite::{params, params_from_iter, Connection};
+use rusqlite::types::Value as SqlValue;
+use tauri::{AppHandle, Manager};
+use tokio::sync::{Mutex, MutexGuard};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use log::{info, error};
+
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+const SAVED_SEARCH_COLUMNS: &str = "id, name, keywords, location, sources, filters, interval_minutes, enabled,
+    notify, last_run, last_result, last_new_jobs";
+
+#[derive(Default)]
+pub struct Scheduler {
+    // Held for the whole of a run so scheduled and manual runs never overlap
+    running: Mutex<()>,
+    paused: AtomicBool,
+}
+
+impl Scheduler {
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    pub async fn lock(&self) -> MutexGuard<'_, ()> {
+        self.running.lock().await
+    }
+}
+
+pub fn start_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SCHEDULER_TICK).await;
+
+            // Reminders and archiving are not searches, so pausing the scheduler doesn't hold them back
+            notify_due_reminders(&app_handle);
+            archive_stale(&app_handle);
+
+            let scheduler = app_handle.state::<Scheduler>();
+            if scheduler.is_paused() {
+                continue;
+            }
+            let _guard = match scheduler.running.try_lock() {
+                Ok(guard) => guard,
+                Err(_) => continue,
+            };
+
+            match due_searches(&app_handle) {
+                Ok(searches) => {
+                    for search in searches {
+                        run_search(&app_handle, &search).await;
+                    }
+                }
+                Err(e) => error!("Failed to load due saved searches: {}", e),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn list_saved_searches(app_handle: AppHandle) -> Result<Vec<SavedSearch>, String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(
+        &format!("SELECT {} FROM saved_searches ORDER BY name", SAVED_SEARCH_COLUMNS)
+    ).map_err(|e| e.to_string())?;
+
+    let searches = stmt.query_map([], saved_search_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(searches)
+}
+
+#[tauri::command]
+pub fn create_saved_search(app_handle: AppHandle, search: SavedSearch) -> Result<SavedSearch, String> {
+    validate(&search)?;
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "INSERT INTO saved_searches (name, keywords, location, sources, filters, interval_minutes, enabled, notify)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            search.name,
+            search.keywords,
+            search.location,
+            serde_json::to_string(&search.sources).map_err(|e| e.to_string())?,
+            serde_json::to_string(&search.filters).map_err(|e| e.to_string())?,
+            search.interval_minutes,
+            search.enabled,
+            search.notify
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_saved_search(&conn, conn.last_insert_rowid() as i32)
+}
+
+#[tauri::command]
+pub fn update_saved_search(app_handle: AppHandle, search_id: i32, search: SavedSearch) -> Result<SavedSearch, String> {
+    validate(&search)?;
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+
+    conn.execute(
+        "UPDATE saved_searches
+         SET name = ?1, keywords = ?2, location = ?3, sources = ?4, filters = ?5, interval_minutes = ?6, enabled = ?7,
+             notify = ?8
+         WHERE id = ?9",
+        params![
+            search.name,
+            search.keywords,
+            search.location,
+            serde_json::to_string(&search.sources).map_err(|e| e.to_string())?,
+            serde_json::to_string(&search.filters).map_err(|e| e.to_string())?,
+            search.interval_minutes,
+            search.enabled,
+            search.notify,
+            search_id
+        ],
+    ).map_err(|e| e.to_string())?;
+
+    get_saved_search(&conn, search_id)
+}
+
+#[tauri::command]
+pub fn delete_saved_search(app_handle: AppHandle, search_id: i32) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+    conn.execute("DELETE FROM saved_searches WHERE id = ?1", [search_id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn run_saved_search(app_handle: AppHandle, search_id: i32) -> Result<SavedSearchRun, String> {
+    let search = {
+        let conn = Connection::open(get_db_path(&app_handle)).map_err(|e| e.to_string())?;
+        get_saved_search(&conn, search_id)?
+    };
+
+    let scheduler = app_handle.state::<Scheduler>();
+    let _guard = scheduler.running.lock().await;
+
+    Ok(run_search(&app_handle, &search).await)
+}
+
+pub fn run_enabled_searches(app_handle: AppHandle) {
+    // Runs every enabled search straight away, whether it is due or not
+    tauri::async_runtime::spawn(async move {
+        let scheduler = app_handle.state::<Scheduler>();
+        let _guard = scheduler.running.lock().await;
+
+        match list_saved_searches(app_handle.clone()) {
+            Ok(searches) => {
+                for search in searches.iter().filter(|search| search.enabled) {
+                    run_search(&app_handle, search).await;
+                }
+            }
+            Err(e) => error!("Failed to load saved searches: {}", e),
+        }
+    });
+}
+
+pub async fn run_search(app_handle: &AppHandle, search: &SavedSearch) -> SavedSearchRun {
+    info!("Running saved search {} ({})", search.id, search.name);
+
+    let query = SearchQuery {
+        keywords: search.keywords.clone(),
+        location: search.location.clone(),
+    };
+    // Every caller holds the scheduler lock, so these runs are queued rather than user triggered
+    let (report, new_job_ids) = search_sources(app_handle, &query, &search.sources, true).await;
+    let mut errors = report.errors;
+
+    let new_jobs = match count_matching(app_handle, &new_job_ids, &search.filters) {
+        Ok(count) => count,
+        Err(e) => {
+            errors.push(e);
+            0
+        }
+    };
+
+    let run = SavedSearchRun {
+        search_id: search.id,
+        name: search.name.clone(),
+        new_jobs,
+        error: if errors.is_empty() { None } else { Some(errors.join("\n")) },
+    };
+
+    let last_result = match &run.error {
+        Some(e) => format!("Failed: {}", e),
+        None => format!("Found {} new jobs", new_jobs),
+    };
+    if let Err(e) = record_run(app_handle, search.id, &last_result, new_jobs) {
+        error!("Failed to record saved search run: {}", e);
+    }
+
+    if new_jobs > 0 {
+        let _ = app_handle.emit_all("new-jobs", &run);
+        notify_new_jobs(app_handle, search, new_jobs);
+    }
+    let _ = app_handle.emit_all("saved-search-run", &run);
+
+    run
+}
+
+fn due_searches(app_handle: &AppHandle) -> Result<Vec<SavedSearch>, String> {
+    let conn = Connection::open(get_db_path(app_handle)).map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}
+         FROM saved_searches
+         WHERE enabled = 1
+           AND (last_run IS NULL OR datetime(last_run, '+' || interval_minutes || ' minutes') <= datetime('now'))",
+        SAVED_SEARCH_COLUMNS
+    )).map_err(|e| e.to_string())?;
+
+    let searches = stmt.query_map([], saved_search_from_row)
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(searches)
+}
+
+fn count_matching(app_handle: &AppHandle, job_ids: &[i32], filters: &JobFilter) -> Result<i32, String> {
+    if job_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let conn = Connection::open(get_db_path(app_handle)).map_err(|e| e.to_string())?;
+    let (filter_sql, filter_values) = filter_clause(filters);
+    let placeholders = vec!["?"; job_ids.len()].join(", ");
+
+    let mut values: Vec<SqlValue> = job_ids.iter().map(|id| SqlValue::Integer(*id as i64)).collect();
+    values.extend(filter_values);
+
+    // Only jobs that will actually show up in the unread list are worth a notification
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM jobs WHERE id IN ({}) AND read = 0 AND hidden = 0{}{}",
+            placeholders, blacklist_clause(&conn), filter_sql
+        ),
+        params_from_iter(values),
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())
+}
+
+fn record_run(app_handle: &AppHandle, search_id: i32, last_result: &str, new_jobs: i32) -> Result<(), String> {
+    let conn = Connection::open(get_db_path(app_handle)).map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE saved_searches SET last_run = datetime('now'), last_result = ?1, last_new_jobs = ?2 WHERE id = ?3",
+        params![last_result, new_jobs, search_id],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn get_saved_search(conn: &Connection, search_id: i32) -> Result<SavedSearch, String> {
+    conn.query_row(
+        &format!("SELECT {} FROM saved_searches WHERE id = ?1", SAVED_SEARCH_COLUMNS),
+        [search_id],
+        saved_search_from_row,
+    ).map_err(|e| e.to_string())
+}
+
+fn saved_search_from_row(row: &rusqlite::Row) -> rusqlite::Result<SavedSearch> {
+    Ok(SavedSearch {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        keywords: row.get(2)?,
+        location: row.get(3)?,
+        sources: serde_json::from_str(&row.get::<_, String>(4)?).unwrap_or_default(),
+        filters: serde_json::from_str(&row.get::<_, String>(5)?).unwrap_or_default(),
+        interval_minutes: row.get(6)?,
+        enabled: row.get(7)?,
+        notify: row.get(8)?,
+        last_run: row.get(9)?,
+        last_result: row.get(10)?,
+        last_new_jobs: row.get(11)?,
+    })
+}
+
+fn validate(search: &SavedSearch) -> Result<(), String> {
+    if search.keywords.trim().is_empty() {
+        return Err("Please enter job keywords".to_string());
+    }
+    if search.sources.is_empty() {
+        return Err("Pick at least one job source".to_string());
+    }
+    if search.interval_minutes < 5 {
+        return Err("Saved searches can run at most every 5 minutes".to_string());
+    }
+    Ok(())
+}
@@ -1,6 +1,8 @@
-use crate::schemas::{Job, JobUpdate, Stats};
+use crate::schemas::{Job, JobFilter, JobUpdate, Stats};
+use crate::companies::blacklist_clause;
 use crate::helpers::get_db_path;
-use rusqlite::Connection;
+use rusqlite::{params, params_from_iter, Connection};
+use rusqlite::types::Value as SqlValue;
 use tauri::async_runtime::spawn;
 use log::{info, error};
 use tauri::AppHandle;
@@ -39,22 +41,18 @@ pub fn start_api_server(app_handle:&AppHandle) -> Result<(), String> {
                 let tx_stdout = tx.clone();
                 thread::spawn(move || {
                     let reader = BufReader::new(stdout);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            let _ = tx_stdout.send(format!("STDOUT: {}", line));
-                            info!("API Server STDOUT: {}", line);
-                        }
+                    for line in reader.lines().map_while(Result::ok) {
+                        let _ = tx_stdout.send(format!("STDOUT: {}", line));
+                        info!("API Server STDOUT: {}", line);
                     }
                 });
 
                 let tx_stderr = tx.clone();
                 thread::spawn(move || {
                     let reader = BufReader::new(stderr);
-                    for line in reader.lines() {
-                        if let Ok(line) = line {
-                            let _ = tx_stderr.send(format!("STDERR: {}", line));
-                            error!("API Server STDERR: {}", line);
-                        }
+                    for line in reader.lines().map_while(Result::ok) {
+                        let _ = tx_stderr.send(format!("STDERR: {}", line));
+                        error!("API Server STDERR: {}", line);
                     }
                 });
 
@@ -79,34 +77,154 @@ pub fn start_api_server(app_handle:&AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+pub const JOB_COLUMNS: &str = "id, uniqueid, title, company, location, salary, jobkey, fetched_date, read, appliedto, source,
+    match_score, matched_skills, missing_skills, description, requirements, apply_url,
+    extracted_requirements, salary_min, salary_max, salary_currency, salary_period, salary_annual,
+    city, region, country, work_mode,
+    (SELECT json_group_array(tags.name) FROM job_tags JOIN tags ON tags.id = job_tags.tag_id
+     WHERE job_tags.job_id = jobs.id) AS tags,
+    company_id, hidden, archived_at";
+
+pub fn job_from_row(row: &rusqlite::Row) -> rusqlite::Result<Job> {
+    // Columns must be selected in JOB_COLUMNS order
+    Ok(Job {
+        id: row.get(0)?,
+        uniqueid: row.get(1)?,
+        title: row.get(2)?,
+        company: row.get(3)?,
+        location: row.get(4)?,
+        salary: row.get(5)?,
+        jobkey: row.get(6)?,
+        fetched_date: row.get(7)?,
+        read: row.get(8)?,
+        appliedto: row.get(9)?,
+        source: row.get(10)?,
+        match_score: row.get(11)?,
+        matched_skills: json_list(row.get(12)?),
+        missing_skills: json_list(row.get(13)?),
+        description: row.get::<_, Option<String>>(14)?.unwrap_or_default(),
+        requirements: row.get::<_, Option<String>>(15)?.unwrap_or_default(),
+        apply_url: row.get::<_, Option<String>>(16)?.unwrap_or_default(),
+        extracted_requirements: row.get::<_, Option<String>>(17)?
+            .and_then(|v| serde_json::from_str(&v).ok()),
+        salary_min: row.get(18)?,
+        salary_max: row.get(19)?,
+        salary_currency: row.get(20)?,
+        salary_period: row.get(21)?,
+        salary_annual: row.get(22)?,
+        city: row.get(23)?,
+        region: row.get(24)?,
+        country: row.get(25)?,
+        work_mode: row.get(26)?,
+        tags: json_list(row.get(27)?),
+        company_id: row.get(28)?,
+        hidden: row.get(29)?,
+        archived_at: row.get(30)?,
+    })
+}
+
+pub fn filter_clause(filter: &JobFilter) -> (String, Vec<SqlValue>) {
+    let tag_clause;
+    let mut clauses: Vec<&str> = Vec::new();
+    let mut values = Vec::new();
+
+    if let Some(min_salary) = filter.min_salary {
+        clauses.push("salary_annual >= ?");
+        values.push(SqlValue::Real(min_salary));
+    }
+    if let Some(currency) = &filter.currency {
+        clauses.push("salary_currency = ?");
+        values.push(SqlValue::Text(currency.to_uppercase()));
+    }
+    if let Some(work_mode) = &filter.work_mode {
+        clauses.push("work_mode = ?");
+        values.push(SqlValue::Text(work_mode.to_lowercase()));
+    }
+    if let Some(country) = &filter.country {
+        clauses.push("country = ? COLLATE NOCASE");
+        values.push(SqlValue::Text(country.clone()));
+    }
+    if let Some(region) = &filter.region {
+        clauses.push("region = ? COLLATE NOCASE");
+        values.push(SqlValue::Text(region.clone()));
+    }
+    if let Some(city) = &filter.city {
+        clauses.push("city LIKE ?");
+        values.push(SqlValue::Text(format!("%{}%", city)));
+    }
+    if let Some(applied) = filter.applied {
+        clauses.push("appliedto = ?");
+        values.push(SqlValue::Integer(applied as i64));
+    }
+    if let Some(hidden) = filter.hidden {
+        clauses.push("hidden = ?");
+        values.push(SqlValue::Integer(hidden as i64));
+    }
+    if let Some(archived) = filter.archived {
+        clauses.push(if archived { "archived_at IS NOT NULL" } else { "archived_at IS NULL" });
+    }
+    // Tag names are unique ignoring case, so "Remote" and "remote" only count once towards the total
+    let mut tags: Vec<&str> = Vec::new();
+    for tag in filter.tags.iter().map(|tag| tag.trim()).filter(|tag| !tag.is_empty()) {
+        if !tags.iter().any(|seen| seen.to_lowercase() == tag.to_lowercase()) {
+            tags.push(tag);
+        }
+    }
+    if !tags.is_empty() {
+        // Jobs must carry every requested tag
+        let placeholders = vec!["?"; tags.len()].join(", ");
+        tag_clause = format!(
+            "id IN (SELECT job_tags.job_id FROM job_tags JOIN tags ON tags.id = job_tags.tag_id
+                    WHERE tags.name IN ({}) GROUP BY job_tags.job_id HAVING COUNT(*) = {})",
+            placeholders,
+            tags.len()
+        );
+        clauses.push(&tag_clause);
+        values.extend(tags.iter().map(|tag| SqlValue::Text(tag.to_string())));
+    }
+
+    let sql = clauses.iter().map(|clause| format!(" AND {}", clause)).collect::<String>();
+    (sql, values)
+}
+
+pub fn record_status(conn: &Connection, job_id: i32, status: &str) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT INTO job_history (job_id, status) VALUES (?1, ?2)",
+        params![job_id, status],
+    )?;
+    Ok(())
+}
+
+fn json_list(value: Option<String>) -> Vec<String> {
+    value.and_then(|v| serde_json::from_str(&v).ok()).unwrap_or_default()
+}
+
 #[tauri::command]
-pub async fn get_unread_jobs(app_handle:AppHandle) -> Result<Vec<Job>, String> {
+pub async fn get_unread_jobs(app_handle:AppHandle, sort_by: Option<String>, filter: Option<JobFilter>) -> Result<Vec<Job>, String> {
     let jobs = spawn(async move {
         let db_path = get_db_path(&app_handle);
         let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
 
-        let mut stmt = conn.prepare(
-            "SELECT id, uniqueid, title, company, location, salary, jobkey, fetched_date, read, appliedto, source
+        let order_by = match sort_by.as_deref() {
+            Some("score") => "match_score IS NULL, match_score DESC, fetched_date DESC",
+            Some("salary") => "salary_annual IS NULL, salary_annual DESC, fetched_date DESC",
+            _ => "fetched_date DESC",
+        };
+        let filter = filter.unwrap_or_default();
+        // Jobs hidden by a rule or archived stay out of the queue unless the filter explicitly asks for them
+        let hidden_sql = if filter.hidden.is_none() { " AND hidden = 0" } else { "" };
+        let archived_sql = if filter.archived.is_none() { " AND archived_at IS NULL" } else { "" };
+        let (filter_sql, filter_values) = filter_clause(&filter);
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {}
              FROM jobs
-             WHERE read = 0
-             ORDER BY fetched_date DESC"
-        ).map_err(|e| e.to_string())?;
+             WHERE read = 0{}{}{}{}
+             ORDER BY {}",
+            JOB_COLUMNS, hidden_sql, archived_sql, blacklist_clause(&conn), filter_sql, order_by
+        )).map_err(|e| e.to_string())?;
 
-        let jobs = stmt.query_map([], |row| {
-            Ok(Job {
-                id: row.get(0)?,
-                uniqueid: row.get(1)?,
-                title: row.get(2)?,
-                company: row.get(3)?,
-                location: row.get(4)?,
-                salary: row.get(5)?,
-                jobkey: row.get(6)?,
-                fetched_date: row.get(7)?,
-                read: row.get(8)?,
-                appliedto: row.get(9)?,
-                source: row.get(10)?,
-            })
-        }).map_err(|e| e.to_string())?
+        let jobs = stmt.query_map(params_from_iter(filter_values), job_from_row).map_err(|e| e.to_string())?
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
 
@@ -127,6 +245,23 @@ pub fn update_job(app_handle:AppHandle, job_id: i32, job_update: JobUpdate) -> R
             "UPDATE jobs SET read = ?1 WHERE id = ?2",
             [read as i32, job_id],
         ).map_err(|e| e.to_string())?;
+        record_status(&conn, job_id, if read { "read" } else { "unread" }).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(hidden) = job_update.hidden {
+        conn.execute(
+            "UPDATE jobs SET hidden = ?1 WHERE id = ?2",
+            [hidden as i32, job_id],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    if let Some(archived) = job_update.archived {
+        conn.execute(
+            "UPDATE jobs SET archived_at = CASE WHEN ?1 THEN COALESCE(archived_at, datetime('now')) ELSE NULL END
+             WHERE id = ?2",
+            [archived as i32, job_id],
+        ).map_err(|e| e.to_string())?;
+        record_status(&conn, job_id, if archived { "archived" } else { "unarchived" }).map_err(|e| e.to_string())?;
     }
 
     if let Some(appliedto) = job_update.appliedto {
@@ -134,6 +269,7 @@ pub fn update_job(app_handle:AppHandle, job_id: i32, job_update: JobUpdate) -> R
             "UPDATE jobs SET appliedto = ?1, read = 1 WHERE id = ?2",
             [appliedto as i32, job_id],
         ).map_err(|e| e.to_string())?;
+        record_status(&conn, job_id, if appliedto { "applied" } else { "not applied" }).map_err(|e| e.to_string())?;
 
         if appliedto {
             conn.execute(
@@ -144,23 +280,9 @@ pub fn update_job(app_handle:AppHandle, job_id: i32, job_update: JobUpdate) -> R
     }
 
     let job = conn.query_row(
-        "SELECT * FROM jobs WHERE id = ?1",
+        &format!("SELECT {} FROM jobs WHERE id = ?1", JOB_COLUMNS),
         [job_id],
-        |row| {
-            Ok(Job {
-                id: row.get(0)?,
-                uniqueid: row.get(1)?,
-                title: row.get(2)?,
-                company: row.get(3)?,
-                location: row.get(4)?,
-                salary: row.get(5)?,
-                jobkey: row.get(6)?,
-                fetched_date: row.get(7)?,
-                read: row.get(8)?,
-                appliedto: row.get(9)?,
-                source: row.get(10)?,
-            })
-        },
+        job_from_row,
     ).map_err(|e| e.to_string())?;
 
     Ok(job)
@@ -187,3 +309,47 @@ pub async fn get_stats(app_handle:AppHandle) -> Result<Stats, String> {
 
     stats.map_err(|e| format!("Task join error: {}", e))?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::test_connection;
+    use crate::ingest::insert_job;
+    use crate::jobnotes::add_tags;
+    use crate::schemas::NewJob;
+    use rusqlite::params_from_iter;
+
+    fn matching_ids(conn: &Connection, filter: &JobFilter) -> Vec<i32> {
+        let (sql, values) = filter_clause(filter);
+        let mut stmt = conn.prepare(&format!("SELECT id FROM jobs WHERE 1 = 1{} ORDER BY id", sql)).unwrap();
+        let ids = stmt.query_map(params_from_iter(values), |row| row.get(0)).unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        ids
+    }
+
+    #[test]
+    fn jobs_can_be_filtered_by_region() {
+        let conn = test_connection();
+        let lombardy = insert_job(&conn, &NewJob { uniqueid: "milano".to_string(), ..Default::default() }).unwrap().unwrap();
+        let texas = insert_job(&conn, &NewJob { uniqueid: "austin".to_string(), ..Default::default() }).unwrap().unwrap();
+        conn.execute("UPDATE jobs SET region = 'Lombardia' WHERE id = ?1", [lombardy]).unwrap();
+        conn.execute("UPDATE jobs SET region = 'TX' WHERE id = ?1", [texas]).unwrap();
+
+        let filter = JobFilter { region: Some("lombardia".to_string()), ..Default::default() };
+        assert_eq!(matching_ids(&conn, &filter), vec![lombardy]);
+    }
+
+    #[test]
+    fn repeated_tags_ignore_case() {
+        let conn = test_connection();
+        let job_id = insert_job(&conn, &NewJob { uniqueid: "tagged".to_string(), ..Default::default() }).unwrap().unwrap();
+        insert_job(&conn, &NewJob { uniqueid: "untagged".to_string(), ..Default::default() }).unwrap();
+        add_tags(&conn, job_id, &["Remote".to_string(), "rust".to_string()]).unwrap();
+
+        let filter = |tags: &[&str]| JobFilter { tags: tags.iter().map(|tag| tag.to_string()).collect(), ..Default::default() };
+        assert_eq!(matching_ids(&conn, &filter(&["remote", "Remote", " REMOTE "])), vec![job_id]);
+        assert_eq!(matching_ids(&conn, &filter(&["remote", "RUST"])), vec![job_id]);
+        assert!(matching_ids(&conn, &filter(&["remote", "go"])).is_empty());
+    }
+}
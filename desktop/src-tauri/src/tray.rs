@@ -0,0 +1,76 @@
+use crate::searches::{Scheduler, run_enabled_searches};
+use crate::notifications::clear_new_jobs_badge;
+
+use tauri::{AppHandle, CustomMenuItem, GlobalWindowEvent, Manager, SystemTray, SystemTrayEvent, SystemTrayMenu, SystemTrayMenuItem, WindowEvent};
+use log::error;
+
+pub fn build_tray() -> SystemTray {
+    let menu = SystemTrayMenu::new()
+        .add_item(CustomMenuItem::new("open", "Open Solicit"))
+        .add_item(CustomMenuItem::new("run_searches", "Run saved searches now"))
+        .add_item(CustomMenuItem::new("pause", "Pause scheduler"))
+        .add_native_item(SystemTrayMenuItem::Separator)
+        .add_item(CustomMenuItem::new("quit", "Quit"));
+
+    SystemTray::new().with_menu(menu).with_tooltip("Solicit")
+}
+
+pub fn handle_tray_event(app_handle: &AppHandle, event: SystemTrayEvent) {
+    match event {
+        SystemTrayEvent::LeftClick { .. } => show_main_window(app_handle),
+        SystemTrayEvent::MenuItemClick { id, .. } => match id.as_str() {
+            "open" => show_main_window(app_handle),
+            "run_searches" => run_enabled_searches(app_handle.clone()),
+            "pause" => {
+                let paused = !app_handle.state::<Scheduler>().is_paused();
+                set_scheduler_paused(app_handle.clone(), paused);
+            }
+            "quit" => app_handle.exit(0),
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+pub fn handle_window_event(event: GlobalWindowEvent) {
+    match event.event() {
+        // Closing only hides the window so saved searches keep running from the tray
+        WindowEvent::CloseRequested { api, .. } => {
+            if let Err(e) = event.window().hide() {
+                error!("Failed to hide window: {}", e);
+                return;
+            }
+            api.prevent_close();
+        }
+        // Looking at the app counts as having seen the new jobs
+        WindowEvent::Focused(true) => clear_new_jobs_badge(event.window().app_handle()),
+        _ => {}
+    }
+}
+
+#[tauri::command]
+pub fn set_scheduler_paused(app_handle: AppHandle, paused: bool) {
+    app_handle.state::<Scheduler>().set_paused(paused);
+
+    let title = if paused { "Resume scheduler" } else { "Pause scheduler" };
+    if let Err(e) = app_handle.tray_handle().get_item("pause").set_title(title) {
+        error!("Failed to update tray menu: {}", e);
+    }
+    let _ = app_handle.emit_all("scheduler-paused", paused);
+}
+
+#[tauri::command]
+pub fn is_scheduler_paused(app_handle: AppHandle) -> bool {
+    app_handle.state::<Scheduler>().is_paused()
+}
+
+fn show_main_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_window("main") {
+        let shown = window.show()
+            .and_then(|_| window.unminimize())
+            .and_then(|_| window.set_focus());
+        if let Err(e) = shown {
+            error!("Failed to show window: {}", e);
+        }
+    }
+}
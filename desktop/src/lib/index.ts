@@ -1 +1 @@
-export type { Job, Stats, ApplicantDetails, SavedJobDescription } from './schemas'
+export type { Job, Stats, ApplicantDetails, SavedJobDescription, CvDetails, SearchReport, SearchAllReport } from './schemas'
@@ -8,7 +8,36 @@ export interface Job {
     fetched_date: string,
     read: boolean,
     appliedto: boolean,
-    source: string
+    source: string,
+    match_score: number | null,
+    matched_skills: string[],
+    missing_skills: string[],
+    description: string,
+    requirements: string,
+    apply_url: string,
+    extracted_requirements: JobRequirements | null,
+    salary_min: number | null,
+    salary_max: number | null,
+    salary_currency: string | null,
+    salary_period: string | null,
+    salary_annual: number | null,
+    city: string | null,
+    region: string | null,
+    country: string | null,
+    work_mode: 'remote' | 'hybrid' | 'onsite' | null,
+    tags: string[],
+    company_id: number | null,
+    hidden: boolean,
+    archived_at: string | null
+}
+
+export interface JobRequirements {
+    must_have: string[],
+    nice_to_have: string[],
+    seniority: string,
+    years_experience: number | null,
+    remote_policy: string,
+    salary_hints: string[]
 }
 
 export interface ApplicantDetails {
@@ -20,6 +49,57 @@ export interface ApplicantDetails {
     certificates: string
 }
 
+export interface ExperienceEntry {
+    employer: string,
+    role: string,
+    start_date: string,
+    end_date: string,
+    summary: string,
+    bullets: string[]
+}
+
+export interface EducationEntry {
+    degree: string,
+    area: string,
+    institution: string,
+    start_date: string,
+    year: string
+}
+
+export interface Project {
+    name: string,
+    description: string,
+    url: string,
+    highlights: string[]
+}
+
+export interface Certificate {
+    name: string,
+    issuer: string,
+    date: string
+}
+
+export interface Skill {
+    name: string,
+    level: string,
+    keywords: string[]
+}
+
+export interface CvDetails {
+    name: string,
+    label: string,
+    email: string,
+    phone: string,
+    url: string,
+    summary: string,
+    experience: ExperienceEntry[],
+    interests: string[],
+    projects: Project[],
+    education: EducationEntry[],
+    certificates: Certificate[],
+    skills: Skill[]
+}
+
 export interface JobDescription extends ApplicantDetails {
     jobTitle: string,
     company: string,
@@ -34,6 +114,223 @@ export interface SavedJobDescription {
 	keyRequirements: ''
 }
 
+export interface JobFilter {
+    min_salary?: number | null,
+    currency?: string | null,
+    work_mode?: 'remote' | 'hybrid' | 'onsite' | null,
+    country?: string | null,
+    region?: string | null,
+    city?: string | null,
+    applied?: boolean | null,
+    hidden?: boolean | null,
+    archived?: boolean | null,
+    tags?: string[]
+}
+
+export interface JobNote {
+    id: number,
+    job_id: number,
+    body: string,
+    created_at: string,
+    updated_at: string
+}
+
+export interface Tag {
+    id: number,
+    name: string,
+    job_count: number
+}
+
+export interface JobContact {
+    id: number,
+    job_id: number,
+    name: string,
+    email: string,
+    role: string
+}
+
+export interface ArchiveSettings {
+    enabled: boolean,
+    after_days: number
+}
+
+export interface JobRule {
+    id: number,
+    name: string,
+    field: 'title' | 'company' | 'location' | 'description',
+    kind: 'contains' | 'regex' | 'salary_floor',
+    pattern: string,
+    min_salary: number | null,
+    mode: 'include' | 'exclude',
+    action: 'hide' | 'tag' | 'mark_read',
+    tag: string,
+    enabled: boolean
+}
+
+export interface Company {
+    id: number,
+    name: string,
+    website: string,
+    notes: string,
+    rating: number | null,
+    blocked: boolean,
+    recruiter: boolean,
+    job_count: number
+}
+
+export interface BlacklistSettings {
+    mark_read: boolean,
+    block_recruiters: boolean
+}
+
+export interface JobReminder {
+    id: number,
+    job_id: number,
+    kind: 'follow_up' | 'interview' | 'deadline',
+    title: string,
+    due_at: string,
+    duration_minutes: number,
+    location: string,
+    link: string,
+    notes: string,
+    notify_before_minutes: number,
+    notified: boolean,
+    done: boolean,
+    job_title: string,
+    company: string,
+    apply_url: string,
+    sequence: number,
+    updated_at: string
+}
+
+export interface JobStatusChange {
+    status: string,
+    changed_at: string
+}
+
+export interface JobImportSummary {
+    added: number,
+    merged: number
+}
+
+export interface SavedSearch {
+    id: number,
+    name: string,
+    keywords: string,
+    location: string,
+    sources: string[],
+    filters: JobFilter,
+    interval_minutes: number,
+    enabled: boolean,
+    notify: boolean,
+    last_run: string | null,
+    last_result: string | null,
+    last_new_jobs: number
+}
+
+export interface NewJob {
+    uniqueid: string,
+    title: string,
+    company: string,
+    location: string,
+    salary: string,
+    jobkey: string,
+    source: string,
+    description: string,
+    apply_url: string,
+    read: boolean,
+    appliedto: boolean
+}
+
+export interface AtsBoards {
+    greenhouse: string[],
+    lever: string[]
+}
+
+export interface Feed {
+    id: number,
+    name: string,
+    url: string,
+    enabled: boolean,
+    last_fetched: string | null,
+    last_error: string | null
+}
+
+export interface SearchReport {
+    source: string,
+    keywords: string,
+    location: string,
+    pages_fetched: number,
+    jobs_seen: number,
+    inserted: number,
+    duplicates_skipped: number,
+    errors: string[],
+    duration_ms: number,
+    run_at: string | null
+}
+
+export interface SearchQuery {
+    keywords: string,
+    location: string
+}
+
+export interface SearchAllReport {
+    keywords: string,
+    location: string,
+    reports: SearchReport[],
+    jobs_seen: number,
+    inserted: number,
+    duplicates_skipped: number,
+    errors: string[],
+    duration_ms: number
+}
+
+export interface SearchProgress {
+    source: string,
+    page: number,
+    total_pages: number | null,
+    jobs_found: number,
+    message: string
+}
+
+export interface CrawlPolicy {
+    delay_ms: number,
+    max_requests_per_hour: number,
+    max_retries: number,
+    backoff_ms: number,
+    min_run_interval_minutes: number,
+    user_agent: string,
+    respect_robots: boolean
+}
+
+export interface BackupFile {
+    name: string,
+    size: number,
+    sha256: string
+}
+
+export interface BackupManifest {
+    format: string,
+    version: number,
+    app_version: string,
+    created_at: string,
+    includes_secrets: boolean,
+    files: BackupFile[]
+}
+
+export interface NotificationSettings {
+    disabled: boolean,
+    quiet_hours_start: string | null,
+    quiet_hours_end: string | null
+}
+
+export interface SavedSearchRun {
+    search_id: number,
+    name: string,
+    new_jobs: number,
+    error: string | null
+}
+
 export interface Stats {
     uniquejobs: number,
     appliedjobs: number